@@ -0,0 +1,213 @@
+// benchmarks for the slow parts of a decode, run over made up pages the size the mission serves and twice that.
+// cargo bench, then compare against target/criterion from an earlier run
+
+use std::collections::HashSet;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use hts_prog6::{
+    decode_page,
+    draw::{commands, get_curve_coordinates, get_line_coordinates, DrawCommand},
+    rasterize, segment,
+    segment::analyze_character,
+    synth::{generate, Layout, SyntheticPage},
+    Glyph, Rect, Section, Strategy,
+};
+
+// any seed gives a page that segments, see tests/synth.rs
+const SEED: u64 = 0;
+
+struct Fixture {
+    name: &'static str,
+    page: SyntheticPage,
+    commands: Vec<DrawCommand>,
+    curves: Vec<Rect>,
+    lines: Vec<Rect>,
+}
+
+impl Fixture {
+    fn new(name: &'static str, layout: Layout) -> Fixture {
+        let page = generate(SEED, layout);
+        let commands = commands(&page.draw_data).expect("generated pages are whole commands");
+        let (curves, lines) = rasterize(&commands).expect("generated pages always draw");
+
+        Fixture {
+            name,
+            page,
+            commands,
+            curves,
+            lines,
+        }
+    }
+
+    fn all_rects(&self) -> Vec<Rect> {
+        [self.curves.clone(), self.lines.clone()].concat()
+    }
+
+    // the rect segmentation starts from, on the top-most character
+    fn top(&self) -> Rect {
+        *self.all_rects().iter().min_by_key(|i| i.1).unwrap()
+    }
+
+    fn characters(&self) -> Vec<Glyph> {
+        segment(&self.curves, &self.lines).expect("generated pages always segment")
+    }
+}
+
+fn fixtures() -> Vec<Fixture> {
+    vec![
+        Fixture::new("realistic", Layout::REALISTIC),
+        Fixture::new("large", Layout::LARGE),
+    ]
+}
+
+fn curves(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_curve_coordinates");
+
+    // a full circle the size of the mission's bowls, a bigger one, and the biggest sweep the guard lets through
+    for (name, r, e) in [
+        ("r4 circle", 4, 360),
+        ("r9 circle", 9, 360),
+        ("r9 max sweep", 9, 360 * 4),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                get_curve_coordinates(
+                    black_box(400),
+                    black_box(400),
+                    black_box(r),
+                    black_box(30),
+                    black_box(e),
+                )
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn lines(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_line_coordinates");
+
+    for (name, x2, y2) in [
+        ("shallow", 412, 403),
+        ("steep", 403, 412),
+        ("long", 900, 650),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                get_line_coordinates(black_box(400), black_box(400), black_box(x2), black_box(y2))
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn rasterization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rasterize");
+
+    for fixture in fixtures() {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(fixture.name),
+            &fixture.commands,
+            |b, commands| b.iter(|| rasterize(commands)),
+        );
+    }
+
+    group.finish();
+}
+
+fn characters(c: &mut Criterion) {
+    let mut group = c.benchmark_group("analyze_character");
+
+    for fixture in fixtures() {
+        let all = fixture.all_rects();
+        let top = fixture.top();
+
+        group.bench_function(fixture.name, |b| b.iter(|| analyze_character(&all, &[top])));
+    }
+
+    group.finish();
+}
+
+fn next(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_next");
+
+    // the step from the first character to the second, with the first already taken out like segment does
+    for fixture in fixtures() {
+        let all = fixture.all_rects();
+        let first = analyze_character(&all, &[fixture.top()]);
+        let rest: Vec<Rect> = all.into_iter().filter(|i| !first.contains(i)).collect();
+
+        group.bench_function(fixture.name, |b| {
+            b.iter(|| Section::A.get_next(&first, &rest))
+        });
+    }
+
+    group.finish();
+}
+
+fn segmentation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("segment");
+    group.sample_size(10);
+
+    for fixture in fixtures() {
+        group.bench_function(fixture.name, |b| {
+            b.iter(|| segment(&fixture.curves, &fixture.lines))
+        });
+    }
+
+    group.finish();
+}
+
+fn recognition(c: &mut Criterion) {
+    let mut group = c.benchmark_group("identify_char");
+
+    // every character on the page, one after the other, like recognise does
+    for fixture in fixtures() {
+        let glyphs = fixture.characters();
+        let arcs: HashSet<(i32, i32)> = fixture.curves.iter().map(|i| (i.0, i.1)).collect();
+
+        for strategy in [Strategy::Rules, Strategy::Moments] {
+            group.bench_with_input(
+                BenchmarkId::new(fixture.name, format!("{:?}", strategy)),
+                &strategy,
+                |b, strategy| {
+                    b.iter(|| {
+                        for glyph in glyphs.iter() {
+                            let _ = black_box(glyph.identify_char(&arcs, strategy));
+                        }
+                    })
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn pipeline(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode_page");
+    group.sample_size(10);
+
+    for fixture in fixtures() {
+        let html = fixture.page.html();
+        group.bench_function(fixture.name, |b| b.iter(|| decode_page(&html)));
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    curves,
+    lines,
+    rasterization,
+    characters,
+    next,
+    segmentation,
+    recognition,
+    pipeline
+);
+criterion_main!(benches);
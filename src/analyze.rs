@@ -1,6 +1,18 @@
-use crate::{HTSError, Section};
+use std::{collections::HashSet, error::Error, fmt, sync::Arc};
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bitmap::GlyphBitmap,
+    draw::Rect,
+    glyph::Glyph,
+    model::{features, Model},
+    moments::{Descriptor, ReferenceSet},
+    orientation::{Orientation, TOLERANCE},
+    skeleton::{NodeKind, StrokeGraph},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CharacterID {
     A,
     B,
@@ -20,54 +32,299 @@ pub enum CharacterID {
     Nine,
 }
 
-pub struct Coordinates {
-    x: i32,
-    y: i32,
-    w: i32,
-    h: i32,
-}
+const CHARACTERS: [(CharacterID, char); 16] = [
+    (CharacterID::A, 'A'),
+    (CharacterID::B, 'B'),
+    (CharacterID::C, 'C'),
+    (CharacterID::D, 'D'),
+    (CharacterID::E, 'E'),
+    (CharacterID::F, 'F'),
+    (CharacterID::Zero, '0'),
+    (CharacterID::One, '1'),
+    (CharacterID::Two, '2'),
+    (CharacterID::Three, '3'),
+    (CharacterID::Four, '4'),
+    (CharacterID::Five, '5'),
+    (CharacterID::Six, '6'),
+    (CharacterID::Seven, '7'),
+    (CharacterID::Eight, '8'),
+    (CharacterID::Nine, '9'),
+];
+
+impl CharacterID {
+    pub fn of(c: char) -> Option<CharacterID> {
+        CHARACTERS.iter().find(|i| i.1 == c).map(|i| i.0)
+    }
 
-impl Coordinates {
-    // if it's not an arc, then it's a line
-    pub fn is_arc(&self, arcs: Vec<(i32, i32, i32, i32)>) -> bool {
-        if self.w == 1 && self.h == 1 {
-            arcs.iter().any(|i| i.0 == self.x && i.1 == self.y)
-        } else {
-            false
+    pub fn value(self) -> char {
+        CHARACTERS.iter().find(|i| i.0 == self).map_or('?', |i| i.1)
+    }
+
+    pub fn character(self) -> Character {
+        Character {
+            id: self,
+            val: self.value().to_string(),
         }
     }
+
+    // the character that's this one turned half way round. no descriptor that ignores turning can tell them apart
+    pub fn turned(self) -> Option<CharacterID> {
+        match self {
+            CharacterID::Six => Some(CharacterID::Nine),
+            CharacterID::Nine => Some(CharacterID::Six),
+            _ => None,
+        }
+    }
+}
+
+// how identify_char reads a glyph
+#[derive(Debug, Clone, Default)]
+pub enum Strategy {
+    // the hand written rules, asked of the glyph turned upright by its angle
+    #[default]
+    Rules,
+    // the nearest reference by shape descriptors that don't care how the glyph is turned
+    Moments,
+    // a classifier trained on labelled glyphs, loaded from a model file
+    Learned(Arc<Model>),
+}
+
+// (highest x, lowest x, highest y, lowest y) coordinates of a character
+type ExtremeCoordinates = (
+    Vec<(i32, i32, i32, i32)>,
+    Vec<(i32, i32, i32, i32)>,
+    Vec<(i32, i32, i32, i32)>,
+    Vec<(i32, i32, i32, i32)>,
+);
+
+type CharMethod = fn(&Glyph, &mut DecisionPath) -> (Character, bool);
+
+// if it's not an arc, then it's a line. arcs is the position of every arc pixel on the page
+pub fn is_arc(rect: &Rect, arcs: &HashSet<(i32, i32)>) -> bool {
+    rect.2 == 1 && rect.3 == 1 && arcs.contains(&(rect.0, rect.1))
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Character {
-    id: CharacterID,
-    val: String,
+    pub id: CharacterID,
+    pub val: String,
 }
 
+// every step identify_char took to reach its answer, in order. shown by the inspect command.
+// also keeps track of how sure the rules are and which characters were still possible at the last branch
 #[derive(Debug, Clone)]
-pub struct CharParams {
-    coordinates_vec: Vec<(i32, i32, i32, i32)>,
-    angle: i32,
-    section: Section,
+pub struct DecisionPath {
+    pub steps: Vec<String>,
+    pub confidence: f64,
+    candidates: Vec<CharacterID>,
+}
+
+impl DecisionPath {
+    pub fn new() -> DecisionPath {
+        DecisionPath {
+            steps: Vec::new(),
+            confidence: 1.0,
+            candidates: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, step: String) {
+        self.steps.push(step);
+    }
+
+    // a step that isn't backed by a real rule (a stub or a default). the answer is only as good as the weakest guess made
+    pub fn guess(&mut self, step: String, confidence: f64) {
+        self.steps.push(step);
+        self.confidence = self.confidence.min(confidence);
+    }
+
+    // something that makes the answer less likely to be right whatever it is, however sure the rules were of it
+    pub fn penalise(&mut self, step: String, factor: f64) {
+        self.steps.push(step);
+        self.confidence *= factor;
+    }
+
+    // the characters still in the running after a branch
+    pub fn narrow(&mut self, candidates: &[CharacterID]) {
+        self.candidates = candidates.to_vec();
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, String> {
+        self.steps.iter()
+    }
 }
 
-pub struct Analyze {
-    pub coordinates_vec: Vec<(i32, i32, i32, i32)>,
-    pub coordinates_angle: i32,
-    pub section: Section,
+impl Default for DecisionPath {
+    fn default() -> DecisionPath {
+        DecisionPath::new()
+    }
 }
 
-// TODO
-impl Analyze {
+// why a character couldn't be read
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecognitionFault {
+    // segmentation handed over a character with no rects in it
+    EmptyGlyph,
+    CharacterUnrecognizable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecognitionError {
+    pub glyph: i32,
+    pub angle: i32,
+    pub fault: RecognitionFault,
+}
+
+impl fmt::Display for RecognitionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fault = match self.fault {
+            RecognitionFault::EmptyGlyph => "it has no rects",
+            RecognitionFault::CharacterUnrecognizable => "unable to identify character",
+        };
+
+        write!(
+            f,
+            "character {} (angle {}): {}",
+            self.glyph, self.angle, fault
+        )
+    }
+}
+
+impl Error for RecognitionError {}
+
+// what identify_char settled on, how sure it is, and what else it could have been
+#[derive(Debug, Clone)]
+pub struct Recognition {
+    pub character: Character,
+    pub confidence: f64,
+    pub alternatives: Vec<CharacterID>,
+    pub path: DecisionPath,
+    // how far round the glyph's own shape says it's turned. None for a glyph with no ink, or one the spiral already
+    // puts where the reading order says
+    pub orientation: Option<Orientation>,
+}
+
+// the measurements the rules below look at, pulled together so they can be printed
+#[derive(Debug, Clone)]
+pub struct Features {
+    pub rects: usize,
+    pub arc_pixels: usize,
+    pub line_rects: usize,
+    pub arcs_present: bool,
+    // (left, top, width, height) of the whole character
+    pub bounding_box: (i32, i32, i32, i32),
+    pub highest_x_coords: Vec<(i32, i32, i32, i32)>,
+    pub lowest_x_coords: Vec<(i32, i32, i32, i32)>,
+    pub highest_y_coords: Vec<(i32, i32, i32, i32)>,
+    pub lowest_y_coords: Vec<(i32, i32, i32, i32)>,
+    // the character turned upright, scaled and centred
+    pub bitmap: GlyphBitmap,
+    // bitmap thinned down to its strokes
+    pub strokes: StrokeGraph,
+    // what the moment recogniser compares, None for a glyph with no ink
+    pub moments: Option<Descriptor>,
+}
+
+// how much of the glyph a run of ink has to cover to count as a stroke running its whole height, or a bar across its
+// whole width. not all of it, since turning a glyph upright breaks its lines up a bit
+const STROKE: f64 = 0.8;
+const BAR: f64 = 0.6;
+
+// how much of a stroke has to be drawn by arcs for it to count as one. thinning pulls lines into the middle of the ink,
+// so a line running alongside an arc picks up some of its pixels
+const ARC_SHARE: f64 = 0.3;
+
+// references no more than this much further away than the closest are still possible answers
+const RUNNER_UP: f64 = 1.25;
+
+// and classes the model thinks are at least this fraction as likely as its answer
+const MODEL_RUNNER_UP: f64 = 0.1;
+
+impl Glyph {
     pub fn identify_char(
         &self,
-        // angle: i32,
-        // char_coords: Vec<(i32, i32, i32, i32)>,
-        arcs: Vec<(i32, i32, i32, i32)>,
-        // section: Section,
-    ) -> Result<Character, HTSError> {
+        arcs: &HashSet<(i32, i32)>,
+        strategy: &Strategy,
+    ) -> Result<Character, RecognitionError> {
+        self.identify_char_traced(arcs, strategy, &mut DecisionPath::new())
+    }
+
+    pub fn recognise(
+        &self,
+        arcs: &HashSet<(i32, i32)>,
+        strategy: &Strategy,
+    ) -> Result<Recognition, RecognitionError> {
+        let mut path = DecisionPath::new();
+        let character = self.identify_char_traced(arcs, strategy, &mut path)?;
+
+        let orientation = Orientation::if_needed(self);
+        if let Some(orientation) = &orientation {
+            self.check_angle(orientation, &mut path);
+        }
+
+        let alternatives = path
+            .candidates
+            .iter()
+            .filter(|i| **i != character.id)
+            .copied()
+            .collect();
+
+        Ok(Recognition {
+            character,
+            confidence: path.confidence,
+            alternatives,
+            path,
+            orientation,
+        })
+    }
+
+    // the rules read the glyph turned upright by the angle its place in the reading order gives it. if its shape says
+    // it's turned some other way, the reading order has probably slipped and what it was read as can't be trusted as
+    // much. the further out, the less
+    fn check_angle(&self, orientation: &Orientation, path: &mut DecisionPath) {
+        path.push(format!(
+            "shape says turned {:.0} degrees (fits a {} best), reading order says {:.0}",
+            orientation.angle,
+            orientation.character.character().val,
+            self.frame.rotation
+        ));
+
+        if !orientation.agrees() {
+            path.penalise(
+                format!(
+                    "{:.0} degrees out from the reading order",
+                    orientation.disagreement
+                ),
+                TOLERANCE / orientation.disagreement,
+            );
+        }
+    }
+
+    // same as identify_char, but writes down why it picked what it picked
+    pub fn identify_char_traced(
+        &self,
+        arcs: &HashSet<(i32, i32)>,
+        strategy: &Strategy,
+        path: &mut DecisionPath,
+    ) -> Result<Character, RecognitionError> {
+        if self.rects.is_empty() {
+            return Err(RecognitionError {
+                glyph: self.index,
+                angle: self.angle,
+                fault: RecognitionFault::EmptyGlyph,
+            });
+        }
+
+        match strategy {
+            Strategy::Rules => Ok(self.by_rules(arcs, path)),
+            Strategy::Moments => self.by_moments(arcs, path),
+            Strategy::Learned(model) => self.by_model(arcs, model, path),
+        }
+    }
+
+    fn by_rules(&self, arcs: &HashSet<(i32, i32)>, path: &mut DecisionPath) -> Character {
         // characters that can have both curves and lines: B, D, 5, 2, 9, 0, C, 8, 6, 3
-        let methods: [fn(CharParams) -> (Character, bool); 4] = [
+        let methods: [CharMethod; 4] = [
             Self::is_two_three_or_five,
             Self::is_six_eight_or_nine,
             Self::is_b_d_or_c,
@@ -76,128 +333,309 @@ impl Analyze {
 
         // the order of this array is deliberate
         // characters with no curves: E, F, A, 1, 7, 4
-        let methods_if_no_arcs: [fn(CharParams) -> (Character, bool); 1] = [Self::no_arcs];
-
-        // check if any arcs
-        let arcs_present = self.coordinates_vec.iter().any(|i| {
-            let coordinates = Coordinates {
-                x: i.0,
-                y: i.1,
-                w: i.2,
-                h: i.3,
-            };
-            coordinates.is_arc(arcs.clone())
-        });
-
-        let method_params = CharParams {
-            coordinates_vec: self.coordinates_vec.clone(),
-            angle: self.coordinates_angle,
-            section: self.section,
-        };
+        let methods_if_no_arcs: [CharMethod; 1] = [Self::no_arcs];
+
+        // check if any of its strokes are arcs
+        let strokes = self.strokes(arcs);
+        let arcs_present = self.has_arcs(&strokes, arcs);
+
+        path.push(format!(
+            "arcs present: {}, turned {:.0} degrees",
+            arcs_present, self.frame.rotation
+        ));
+        path.push(format!(
+            "{} strokes, {} ends, {} junctions, {} loops",
+            strokes.edges.len(),
+            strokes.count(NodeKind::End),
+            strokes.count(NodeKind::Junction),
+            strokes.count(NodeKind::Loop)
+        ));
 
         // debug
         if arcs_present {
+            path.narrow(&[
+                CharacterID::B,
+                CharacterID::D,
+                CharacterID::Five,
+                CharacterID::Two,
+                CharacterID::Nine,
+                CharacterID::Zero,
+                CharacterID::C,
+                CharacterID::Eight,
+                CharacterID::Six,
+                CharacterID::Three,
+            ]);
+
             for method in methods {
-                let character = (method)(method_params.clone());
+                let character = (method)(self, path);
 
                 if character.1 {
-                    return Ok(character.0);
+                    return character.0;
                 }
             }
         } else {
             for method in methods_if_no_arcs {
-                let character = (method)(method_params.clone());
+                let character = (method)(self, path);
 
                 if character.1 {
-                    return Ok(character.0);
+                    return character.0;
                 }
             }
         }
 
+        path.guess(String::from("no method matched, falling back to A"), 0.0);
+
         // debug
-        let test_character = Character {
+        Character {
             id: CharacterID::A,
             val: String::from("A"),
+        }
+    }
+
+    // the closest reference by shape alone. the angle is only asked to settle a 6 from a 9
+    fn by_moments(
+        &self,
+        arcs: &HashSet<(i32, i32)>,
+        path: &mut DecisionPath,
+    ) -> Result<Character, RecognitionError> {
+        let Some(descriptor) = Descriptor::of(self, arcs) else {
+            return Err(RecognitionError {
+                glyph: self.index,
+                angle: self.angle,
+                fault: RecognitionFault::EmptyGlyph,
+            });
         };
-        return Ok(test_character);
+        let nearest = ReferenceSet::shared().nearest(&descriptor);
+
+        let closest: Vec<String> = nearest
+            .iter()
+            .take(3)
+            .map(|i| format!("{} at {:.2}", i.0.character().val, i.1))
+            .collect();
+        path.push(format!("closest references: {}", closest.join(", ")));
+
+        let Some(&(mut id, distance)) = nearest.first() else {
+            return Err(RecognitionError {
+                glyph: self.index,
+                angle: self.angle,
+                fault: RecognitionFault::CharacterUnrecognizable,
+            });
+        };
+
+        if let Some(turned) = id.turned() {
+            id = self.settle_turned(id, turned, arcs, path);
+        }
+
+        // anything about as close as the best is still in the running
+        let candidates: Vec<CharacterID> = nearest
+            .iter()
+            .filter(|i| i.1 <= distance * RUNNER_UP)
+            .map(|i| i.0)
+            .collect();
+        path.narrow(&candidates);
+
+        // how much closer the best is than the nearest character that isn't it or its turned twin
+        let next = nearest
+            .iter()
+            .find(|i| i.0 != id && Some(i.0) != id.turned())
+            .map_or(f64::INFINITY, |i| i.1);
+        let margin = if next > 0.0 {
+            1.0 - distance / next
+        } else {
+            0.0
+        };
+        path.guess(
+            format!(
+                "{} is closest by {:.0}%",
+                id.character().val,
+                margin * 100.0
+            ),
+            margin,
+        );
+
+        Ok(id.character())
     }
 
-    fn positive_gradient(i: &(i32, i32, i32, i32), coords: (i32, i32, i32, i32)) -> bool {
-        let (cc_x, cc_y, cc_w, cc_h) = (i.0, i.1, i.2, i.3);
-        let (x, y, w, h) = (coords.0, coords.1, coords.2, coords.3);
+    // whatever the model thinks is most likely, as sure as it is
+    fn by_model(
+        &self,
+        arcs: &HashSet<(i32, i32)>,
+        model: &Model,
+        path: &mut DecisionPath,
+    ) -> Result<Character, RecognitionError> {
+        let classes = model.classify(&features(self, arcs));
+
+        let likely: Vec<String> = classes
+            .iter()
+            .take(3)
+            .map(|i| format!("{} {:.0}%", i.0.value(), i.1 * 100.0))
+            .collect();
+        path.push(format!(
+            "model version {} says {}",
+            model.version,
+            likely.join(", ")
+        ));
+
+        let Some(&(id, p)) = classes.first() else {
+            return Err(RecognitionError {
+                glyph: self.index,
+                angle: self.angle,
+                fault: RecognitionFault::CharacterUnrecognizable,
+            });
+        };
 
-        cc_x < x + w && cc_x > x - cc_w && cc_y < y + h && cc_y > y - cc_h
+        let candidates: Vec<CharacterID> = classes
+            .iter()
+            .filter(|i| i.1 >= p * MODEL_RUNNER_UP)
+            .map(|i| i.0)
+            .collect();
+        path.narrow(&candidates);
+        path.guess(format!("{} is the most likely", id.value()), p);
+
+        Ok(id.character())
     }
 
-    fn negative_gradient(i: &(i32, i32, i32, i32), coords: (i32, i32, i32, i32)) -> bool {
-        let (cc_x, cc_y, cc_w, cc_h) = (i.0, i.1, i.2, i.3);
-        let (x, y, w, h) = (coords.0, coords.1, coords.2, coords.3);
+    // a 6 is a 9 turned round, so which one it is comes down to which way up the glyph is. the loop is at the bottom
+    // of a 6 and the top of a 9
+    fn settle_turned(
+        &self,
+        id: CharacterID,
+        turned: CharacterID,
+        arcs: &HashSet<(i32, i32)>,
+        path: &mut DecisionPath,
+    ) -> CharacterID {
+        let curves: Vec<(f64, f64)> = self
+            .rects
+            .iter()
+            .filter(|i| is_arc(i, arcs))
+            .map(|i| self.frame.to_local((f64::from(i.0), f64::from(i.1))))
+            .collect();
+
+        if curves.is_empty() {
+            path.guess(
+                format!(
+                    "no arcs to tell {} from {}",
+                    id.character().val,
+                    turned.character().val
+                ),
+                0.5,
+            );
+            return id;
+        }
+
+        // y grows towards the bottom of the glyph, and the frame is centred on its ink
+        let loop_at = curves.iter().map(|i| i.1).sum::<f64>() / curves.len() as f64;
+        let settled = if loop_at < 0.0 {
+            CharacterID::Nine
+        } else {
+            CharacterID::Six
+        };
+
+        path.guess(
+            format!(
+                "6 and 9 are the same shape turned round, and the loop is nearer the top: {}",
+                loop_at < 0.0
+            ),
+            0.5,
+        );
+        settled
+    }
 
-        cc_x < x + w && cc_x > x - cc_w && cc_y < y + h && cc_y > y - cc_h
+    // the glyph's skeleton, with how much of each stroke the page drew as an arc
+    pub fn strokes(&self, arcs: &HashSet<(i32, i32)>) -> StrokeGraph {
+        let mut strokes = StrokeGraph::of(&GlyphBitmap::of(self));
+        strokes.mark_arcs(&GlyphBitmap::arcs_of(self, arcs));
+        strokes
     }
 
-    fn general_initialization_variables(
-        params: CharParams,
-    ) -> (
-        Vec<(i32, i32, i32, i32)>,
-        Vec<(i32, i32, i32, i32)>,
-        Vec<(i32, i32, i32, i32)>,
-        Vec<(i32, i32, i32, i32)>,
-    ) {
-        let highest_x = params.coordinates_vec.iter().map(|i| i.0).max().unwrap();
+    // whether any stroke is drawn by arcs. a line that happens to cross an arc pixel of the character next to it, or share
+    // one with a curve it joins, doesn't make it one. a glyph too small to leave any strokes once it's thinned goes by
+    // its pixels instead
+    fn has_arcs(&self, strokes: &StrokeGraph, arcs: &HashSet<(i32, i32)>) -> bool {
+        if strokes.edges.is_empty() {
+            return self.rects.iter().any(|i| is_arc(i, arcs));
+        }
+
+        strokes.edges.iter().any(|i| i.arc > ARC_SHARE)
+    }
+
+    // the features the rules look at, worked out from scratch
+    pub fn measure(&self, arcs: &HashSet<(i32, i32)>) -> Features {
+        let arc_pixels = self.rects.iter().filter(|i| is_arc(i, arcs)).count();
+        let strokes = self.strokes(arcs);
+
+        let (highest_x_coords, lowest_x_coords, highest_y_coords, lowest_y_coords) =
+            if self.rects.is_empty() {
+                Default::default()
+            } else {
+                Self::general_initialization_variables(self)
+            };
+
+        Features {
+            rects: self.rects.len(),
+            arc_pixels,
+            line_rects: self.rects.len() - arc_pixels,
+            arcs_present: self.has_arcs(&strokes, arcs),
+            bounding_box: self.bounding_box,
+            highest_x_coords,
+            lowest_x_coords,
+            highest_y_coords,
+            lowest_y_coords,
+            bitmap: GlyphBitmap::of(self),
+            strokes,
+            moments: Descriptor::of(self, arcs),
+        }
+    }
+
+    fn general_initialization_variables(glyph: &Glyph) -> ExtremeCoordinates {
+        let highest_x = glyph.rects.iter().map(|i| i.0).max().unwrap_or_default();
         let mut highest_x_coords = Vec::new();
 
-        for i in params.coordinates_vec.iter() {
+        for i in glyph.rects.iter() {
             if i.0 == highest_x {
                 highest_x_coords.push(i.to_owned())
             }
         }
 
-        let lowest_x = params.coordinates_vec.iter().map(|i| i.0).min().unwrap();
+        let lowest_x = glyph.rects.iter().map(|i| i.0).min().unwrap_or_default();
         let mut lowest_x_coords = Vec::new();
 
-        for i in params.coordinates_vec.iter() {
+        for i in glyph.rects.iter() {
             if i.0 == lowest_x {
                 lowest_x_coords.push(i.to_owned())
             }
         }
 
-        let highest_y = params.coordinates_vec.iter().map(|i| i.1).max().unwrap();
+        let highest_y = glyph.rects.iter().map(|i| i.1).max().unwrap_or_default();
         let mut highest_y_coords = Vec::new();
 
-        for i in params.coordinates_vec.iter() {
+        for i in glyph.rects.iter() {
             if i.1 == highest_y {
                 highest_y_coords.push(i.to_owned())
             }
         }
 
-        let lowest_y = params.coordinates_vec.iter().map(|i| i.1).min().unwrap();
+        let lowest_y = glyph.rects.iter().map(|i| i.1).min().unwrap_or_default();
         let mut lowest_y_coords = Vec::new();
 
-        for i in params.coordinates_vec.iter() {
+        for i in glyph.rects.iter() {
             if i.1 == lowest_y {
                 lowest_y_coords.push(i.to_owned())
             }
         }
 
-        return (
+        (
             highest_x_coords,
             lowest_x_coords,
             highest_y_coords,
             lowest_y_coords,
-        );
-    }
-
-    fn get_top_and_bottom_coords(params: CharParams) {
-        // TODO
-        // depending on section
-        // Sec A = bottom closest to the centre, top away
+        )
     }
 
     // ---------------------------------------methods if no arcs----------------------------------------------------------------
 
     // consider all possibilities: A, 7, 4 1, E, and F
-    fn no_arcs(params: CharParams) -> (Character, bool) {
+    fn no_arcs(glyph: &Glyph, path: &mut DecisionPath) -> (Character, bool) {
         let seven = Character {
             id: CharacterID::Seven,
             val: String::from("7"),
@@ -228,324 +666,103 @@ impl Analyze {
             val: String::from("E"),
         };
 
-        let (highest_x_coords, lowest_x_coords, highest_y_coords, lowest_y_coords) =
-            Self::general_initialization_variables(params.clone());
-
-        // define 0, 90, 180, 270
-
-        match params.section {
-            Section::A => {
-                /*
-                    bottom - highest_y_coords
-                    -------------------------
-                    1 = always positive gradient
-                    A = always negative gradient
-                    7 = always negative gradient
-                    4 = always negative gradient (there's actually a range of angles for which this applies to)
-                    E = both negative and positive gradient
-                    F = always negative gradient
-
-                    top - lowest_y_coords
-                    ---------------------
-                    1 = both positive and negative gradient
-                    A = positive gradient at a few angles; at the rest of the angles, there are two lines of negative gradient
-                    7 = both positive and negative gradient
-                    4 = negative gradient
-                    E = positive gradient
-                    F = positive gradient
-                */
-
-                // TODO: write function to get more accurate set of top and bottom coords
-                let bottom_coords = highest_y_coords[0];
-                let top_coords = lowest_y_coords[0];
-
-                let mut one_or_e = false;
-
-                for i in params.coordinates_vec.iter() {
-                    if Self::positive_gradient(i, bottom_coords) {
-                        one_or_e = true;
-                    }
-                }
-
-                if one_or_e {
-                    for i in params.coordinates_vec.iter() {
-                        if Self::negative_gradient(i, top_coords) {
-                            return (one, true);
-                        }
-                    }
-
-                    return (e, true);
-                } else {
-                    let mut seven_or_f = false;
-
-                    for i in params.coordinates_vec.iter() {
-                        if Self::positive_gradient(i, top_coords) {
-                            seven_or_f = true;
-                        }
-                    }
-
-                    if seven_or_f {
-                        for i in params.coordinates_vec.iter() {
-                            if Self::negative_gradient(i, top_coords) {
-                                return (seven, true);
-                            }
-                        }
-
-                        return (f, true);
-                    } else {
-                        if params.angle < 70 && params.angle > 30 {
-                            let mut number_of_pos_grad_lines_detected = 0;
-                            for i in params.coordinates_vec.iter() {
-                                if Self::negative_gradient(i, top_coords) {
-                                    number_of_pos_grad_lines_detected += 1;
-                                }
-                            }
-
-                            if number_of_pos_grad_lines_detected == 2 {
-                                return (a, true);
-                            }
-                        } else {
-                            for i in params.coordinates_vec.iter() {
-                                if Self::positive_gradient(i, top_coords) {
-                                    return (a, true);
-                                }
-                            }
-                        }
-
-                        return (four, true);
-                    }
-                }
+        path.narrow(&[
+            CharacterID::A,
+            CharacterID::Seven,
+            CharacterID::Four,
+            CharacterID::One,
+            CharacterID::F,
+            CharacterID::E,
+        ]);
+
+        // everything below is asked of the glyph turned upright, so left is its left wherever it is on the circle
+        let upright = GlyphBitmap::of(glyph);
+
+        if upright.stroke_down(0.0, 0.25) > STROKE {
+            path.push(String::from("line down the left: E or F"));
+            path.narrow(&[CharacterID::E, CharacterID::F]);
+
+            if upright.columns_covered(0.8, 1.0) > BAR {
+                path.push(String::from("bar along the bottom: E"));
+                return (e, true);
             }
 
-            Section::B => {}
+            path.push(String::from("nothing along the bottom: F"));
+            return (f, true);
+        }
 
-            Section::C => {}
+        path.push(String::from("no line down the left: 7, 4, A or 1"));
+        path.narrow(&[
+            CharacterID::Seven,
+            CharacterID::Four,
+            CharacterID::A,
+            CharacterID::One,
+        ]);
+
+        if upright.columns_covered(0.0, 0.2) > BAR {
+            path.push(String::from("bar along the top: 7"));
+            return (seven, true);
+        }
 
-            Section::D => {}
+        if upright.stroke_down(0.6, 1.0) > STROKE {
+            path.push(String::from("line down the right: 4"));
+            return (four, true);
         }
 
-        return (a, true);
-    }
+        if upright.stroke_down(0.35, 0.65) > STROKE {
+            path.push(String::from("line down the middle: 1"));
+            return (one, true);
+        }
 
-    // Sample function
-    // fn is_e_f_or_one(params: CharParams) -> (Character, bool) {
-    //     let one = Character {
-    //         id: CharacterID::One,
-    //         val: String::from("1"),
-    //     };
-
-    //     let f = Character {
-    //         id: CharacterID::F,
-    //         val: String::from("F"),
-    //     };
-
-    //     let e = Character {
-    //         id: CharacterID::E,
-    //         val: String::from("E"),
-    //     };
-
-    //     let (highest_x_coords, lowest_x_coords, highest_y_coords, lowest_y_coords) =
-    //         Self::general_initialization_variables(params.clone());
-
-    //     if params.angle == 0 {
-    //         // check if flat at bottom
-    //         for i in highest_y_coords.iter() {
-    //             let (cc_w, cc_h) = (i.2, i.3);
-
-    //             if cc_w > 1 && cc_h == 1 {
-    //                 // is one or e
-    //                 if params.coordinates_vec.len() == 4 {
-    //                     return (e, true);
-    //                 } else {
-    //                     return (one, true);
-    //                 }
-    //             } else if params.coordinates_vec.len() == 3 {
-    //                 return (f, true);
-    //             }
-    //         }
-    //     } else if params.angle == 90 {
-    //         // check if right-most coord is bottom
-    //         for i in highest_x_coords.iter() {
-    //             let (cc_w, cc_h) = (i.2, i.3);
-
-    //             if cc_h > 1 && cc_w == 1 {
-    //                 if params.coordinates_vec.len() == 4 {
-    //                     return (e, true);
-    //                 } else {
-    //                     return (one, true);
-    //                 }
-    //             } else if params.coordinates_vec.len() == 3 {
-    //                 return (f, true);
-    //             }
-    //         }
-    //     } else if params.angle == 180 {
-    //         // check if flat at top
-    //         for i in lowest_y_coords.iter() {
-    //             let (cc_w, cc_h) = (i.2, i.3);
-
-    //             if cc_w > 1 && cc_h == 1 {
-    //                 if params.coordinates_vec.len() == 4 {
-    //                     return (e, true);
-    //                 } else {
-    //                     return (one, true);
-    //                 }
-    //             } else if params.coordinates_vec.len() == 3 {
-    //                 return (f, true);
-    //             }
-    //         }
-    //     } else if params.angle == 270 {
-    //         // check if leftmost coordinates are vertical lines
-    //         for i in lowest_x_coords.iter() {
-    //             let (cc_w, cc_h) = (i.2, i.3);
-
-    //             if cc_h > 1 && cc_w == 1 {
-    //                 if params.coordinates_vec.len() == 4 {
-    //                     return (e, true);
-    //                 } else {
-    //                     return (one, true);
-    //                 }
-    //             } else if params.coordinates_vec.len() == 3 {
-    //                 return (f, true);
-    //             }
-    //         }
-    //     }
-
-    //     match params.section {
-    //         Section::A => {
-    //             let coords = highest_y_coords[0]; // grab random coordinate
-
-    //             let mut one_or_e = false;
-
-    //             for i in params.coordinates_vec.iter() {
-    //                 // if even one coordinate exists that's an "ascending" slope (positive gradient)
-    //                 if Analyze::positive_gradient(i, coords) {
-    //                     one_or_e = true;
-    //                 }
-    //             }
-
-    //             if one_or_e {
-    //                 let coords = lowest_y_coords[0];
-
-    //                 for i in params.coordinates_vec.iter() {
-    //                     // descending slope (negative gradient)
-    //                     if Analyze::negative_gradient(i, coords) {
-    //                         return (one, true);
-    //                     }
-    //                 }
-    //                 return (e, true);
-    //             } else {
-    //                 return (f, true);
-    //             }
-    //         }
-
-    //         Section::B => {
-    //             let coords = highest_x_coords[0];
-
-    //             let mut one_or_e = false;
-
-    //             for i in params.coordinates_vec.iter() {
-    //                 if Analyze::negative_gradient(i, coords) {
-    //                     one_or_e = true;
-    //                 }
-    //             }
-
-    //             if one_or_e {
-    //                 let coords = lowest_x_coords[0];
-
-    //                 for i in params.coordinates_vec.iter() {
-    //                     if Analyze::positive_gradient(i, coords) {
-    //                         return (one, true);
-    //                     }
-    //                 }
-    //                 return (e, true);
-    //             } else {
-    //                 return (f, true);
-    //             }
-    //         }
-
-    //         Section::C => {
-    //             let coords = lowest_y_coords[0];
-
-    //             let mut one_or_e = false;
-
-    //             for i in params.coordinates_vec.iter() {
-    //                 if Analyze::positive_gradient(i, coords) {
-    //                     one_or_e = true;
-    //                 }
-    //             }
-
-    //             if one_or_e {
-    //                 let coords = highest_y_coords[0];
-
-    //                 for i in params.coordinates_vec.iter() {
-    //                     if Analyze::negative_gradient(i, coords) {
-    //                         return (one, true);
-    //                     }
-    //                 }
-    //                 return (e, true);
-    //             } else {
-    //                 return (f, true);
-    //             }
-    //         }
-
-    //         Section::D => {
-    //             let coords = lowest_x_coords[0];
-
-    //             let mut one_or_e = false;
-
-    //             for i in params.coordinates_vec.iter() {
-    //                 if Analyze::negative_gradient(i, coords) {
-    //                     one_or_e = true;
-    //                 }
-    //             }
-
-    //             if one_or_e {
-    //                 let coords = highest_x_coords[0];
-
-    //                 for i in params.coordinates_vec.iter() {
-    //                     if Analyze::positive_gradient(i, coords) {
-    //                         return (one, true);
-    //                     }
-    //                 }
-    //                 return (e, true);
-    //             } else {
-    //                 return (f, true);
-    //             }
-    //         }
-    //     }
-    // }
+        path.guess(String::from("nothing else matched: A"), 0.5);
+        (a, true)
+    }
 
     // --------------------------------------methods if arcs--------------------------------------------------------------------
 
-    fn is_zero(params: CharParams) -> (Character, bool) {
+    fn is_zero(_glyph: &Glyph, path: &mut DecisionPath) -> (Character, bool) {
+        path.guess(String::from("is_zero: not written yet, always 0"), 0.0);
+
         let character = Character {
             id: CharacterID::Zero,
             val: String::from("0"),
         };
-        return (character, true);
+        (character, true)
     }
 
-    fn is_two_three_or_five(params: CharParams) -> (Character, bool) {
+    fn is_two_three_or_five(_glyph: &Glyph, path: &mut DecisionPath) -> (Character, bool) {
+        path.guess(
+            String::from("is_two_three_or_five: not written yet, always 0"),
+            0.0,
+        );
+
         let character = Character {
             id: CharacterID::Zero,
             val: String::from("0"),
         };
-        return (character, true);
+        (character, true)
     }
 
-    fn is_b_d_or_c(params: CharParams) -> (Character, bool) {
+    fn is_b_d_or_c(_glyph: &Glyph, path: &mut DecisionPath) -> (Character, bool) {
+        path.guess(String::from("is_b_d_or_c: not written yet, always 0"), 0.0);
+
         let character = Character {
             id: CharacterID::Zero,
             val: String::from("0"),
         };
-        return (character, true);
+        (character, true)
     }
 
-    fn is_six_eight_or_nine(params: CharParams) -> (Character, bool) {
+    fn is_six_eight_or_nine(_glyph: &Glyph, path: &mut DecisionPath) -> (Character, bool) {
+        path.guess(
+            String::from("is_six_eight_or_nine: not written yet, always 0"),
+            0.0,
+        );
+
         let character = Character {
             id: CharacterID::Zero,
             val: String::from("0"),
         };
-        return (character, true);
+        (character, true)
     }
 }
@@ -0,0 +1,70 @@
+// draws a character in the terminal. '#' is a line, 'o' is an arc pixel. the y axis points down like it does on the page
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{analyze::is_arc, draw::Rect, frame::Frame};
+
+// every pixel a rect covers, and whether it came from an arc
+fn pixels(rects: &[Rect], arcs: &HashSet<(i32, i32)>) -> HashMap<(i32, i32), bool> {
+    let mut pixels = HashMap::new();
+
+    for i in rects.iter() {
+        let is_arc = is_arc(i, arcs);
+
+        for x in i.0..i.0 + i.2 {
+            for y in i.1..i.1 + i.3 {
+                let entry = pixels.entry((x, y)).or_insert(false);
+                *entry |= is_arc;
+            }
+        }
+    }
+
+    pixels
+}
+
+fn grid(pixels: &HashMap<(i32, i32), bool>) -> String {
+    let (min_x, max_x, min_y, max_y) = match (
+        pixels.keys().map(|i| i.0).min(),
+        pixels.keys().map(|i| i.0).max(),
+        pixels.keys().map(|i| i.1).min(),
+        pixels.keys().map(|i| i.1).max(),
+    ) {
+        (Some(a), Some(b), Some(c), Some(d)) => (a, b, c, d),
+        _ => return String::new(),
+    };
+
+    let mut art = String::new();
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            art.push(match pixels.get(&(x, y)) {
+                Some(true) => 'o',
+                Some(false) => '#',
+                None => '.',
+            });
+        }
+        art.push('\n');
+    }
+
+    art
+}
+
+pub fn draw(rects: &[Rect], arcs: &HashSet<(i32, i32)>) -> String {
+    grid(&pixels(rects, arcs))
+}
+
+// turns the character back the other way by its angle around its centre, so it reads the right way up.
+// the characters are turned anticlockwise as the circle goes round, so this turns them clockwise
+pub fn draw_upright(rects: &[Rect], arcs: &HashSet<(i32, i32)>, angle: i32) -> String {
+    let pixels = pixels(rects, arcs);
+
+    if pixels.is_empty() {
+        return String::new();
+    }
+
+    let count = pixels.len() as f64;
+    let centre_x = pixels.keys().map(|i| f64::from(i.0)).sum::<f64>() / count;
+    let centre_y = pixels.keys().map(|i| f64::from(i.1)).sum::<f64>() / count;
+
+    grid(&Frame::new((centre_x, centre_y), f64::from(angle)).upright(&pixels))
+}
@@ -0,0 +1,228 @@
+// a glyph turned upright and drawn into a fixed SIZE x SIZE grid, scaled so its longer side fills the grid and centred
+// in the other. every glyph comes out the same shape whatever its angle, size, or mix of arc pixels and line runs, so
+// anything that compares glyphs can compare these instead
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    draw::bounds,
+    fingerprint::{fnv1a, FNV_OFFSET},
+    glyph::Glyph,
+    transform::{pixels, Affine, Resampling},
+};
+
+// one u16 per row, so it can't go past 16 without widening them
+pub const SIZE: usize = 16;
+
+// a quarter of the sample points is enough to keep one pixel wide strokes whole when they're scaled or turned
+const RESAMPLING: Resampling = Resampling::Supersample {
+    samples: 4,
+    coverage: 0.25,
+};
+
+// bit x of rows[y] is the cell x across and y down. derives Hash and Eq, so it can key a HashMap as it is
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GlyphBitmap {
+    rows: [u16; SIZE],
+}
+
+impl GlyphBitmap {
+    // the glyph as its frame says it's turned
+    pub fn of(glyph: &Glyph) -> GlyphBitmap {
+        GlyphBitmap::fit(glyph.frame.upright(&pixels(&glyph.rects)).into_keys())
+    }
+
+    // just the glyph's arc pixels, in the same place in the grid they have in GlyphBitmap::of. arcs is every arc pixel on the
+    // page
+    pub fn arcs_of(glyph: &Glyph, arcs: &HashSet<(i32, i32)>) -> GlyphBitmap {
+        let marked: HashMap<(i32, i32), bool> = pixels(&glyph.rects)
+            .into_keys()
+            .map(|i| (i, arcs.contains(&i)))
+            .collect();
+        let upright = glyph.frame.upright(&marked);
+        let within: Vec<(i32, i32)> = upright.keys().copied().collect();
+
+        GlyphBitmap::fit_within(upright.into_iter().filter(|i| i.1).map(|i| i.0), &within)
+    }
+
+    // scales and centres a set of pixels into the grid. an empty set gives an empty grid
+    pub fn fit(cells: impl IntoIterator<Item = (i32, i32)>) -> GlyphBitmap {
+        let cells: Vec<(i32, i32)> = cells.into_iter().collect();
+        GlyphBitmap::fit_within(cells.iter().copied(), &cells)
+    }
+
+    // scales and centres cells the way fit would scale and centre within, so part of a glyph lands where it would with
+    // the rest of it
+    pub fn fit_within(
+        cells: impl IntoIterator<Item = (i32, i32)>,
+        within: &[(i32, i32)],
+    ) -> GlyphBitmap {
+        let within: Vec<_> = within.iter().map(|i| (i.0, i.1, 1, 1)).collect();
+        let rects: Vec<_> = cells.into_iter().map(|i| (i.0, i.1, 1, 1)).collect();
+
+        let Some((left, top, right, bottom)) = bounds(&within) else {
+            return GlyphBitmap::default();
+        };
+
+        // bounds stops one past the last pixel, and a pixel at (x, y) reaches half a pixel either side of it
+        let (width, height) = (f64::from(right - left), f64::from(bottom - top));
+        let middle = (
+            f64::from(left + right - 1) / 2.0,
+            f64::from(top + bottom - 1) / 2.0,
+        );
+        let grid_middle = (SIZE as f64 - 1.0) / 2.0;
+
+        let into_grid = Affine::translation(-middle.0, -middle.1)
+            .then(&Affine::scale(SIZE as f64 / width.max(height)))
+            .then(&Affine::translation(grid_middle, grid_middle));
+
+        let mut bitmap = GlyphBitmap::default();
+
+        for (x, y) in into_grid
+            .apply_pixels(&pixels(&rects), RESAMPLING)
+            .into_keys()
+        {
+            bitmap.set(x, y);
+        }
+
+        bitmap
+    }
+
+    // anything outside the grid is left alone
+    pub fn set(&mut self, x: i32, y: i32) {
+        if (0..SIZE as i32).contains(&x) && (0..SIZE as i32).contains(&y) {
+            self.rows[y as usize] |= 1 << x;
+        }
+    }
+
+    pub fn clear(&mut self, x: i32, y: i32) {
+        if (0..SIZE as i32).contains(&x) && (0..SIZE as i32).contains(&y) {
+            self.rows[y as usize] &= !(1 << x);
+        }
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> bool {
+        (0..SIZE as i32).contains(&x)
+            && (0..SIZE as i32).contains(&y)
+            && self.rows[y as usize] & (1 << x) != 0
+    }
+
+    // (x, y) of every set cell, row by row
+    pub fn cells(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        (0..SIZE as i32).flat_map(move |y| {
+            (0..SIZE as i32)
+                .filter(move |x| self.get(*x, y))
+                .map(move |x| (x, y))
+        })
+    }
+
+    pub fn count(&self) -> u32 {
+        self.rows.iter().map(|i| i.count_ones()).sum()
+    }
+
+    // how many cells are set in one and not the other
+    pub fn distance(&self, other: &GlyphBitmap) -> u32 {
+        self.rows
+            .iter()
+            .zip(other.rows.iter())
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum()
+    }
+
+    // fnv-1a over the rows. unlike Hash, which is free to change between rust versions, this stays the same
+    // everywhere, so it can go in files
+    pub fn fingerprint(&self) -> u64 {
+        self.rows
+            .iter()
+            .fold(FNV_OFFSET, |hash, i| fnv1a(hash, &i.to_le_bytes()))
+    }
+
+    // the most of the glyph's rows any one upright stroke starting between left and right covers, where left and right
+    // are fractions of the glyph's width. the glyph only fills the grid one way, so this goes by what's set rather than
+    // the whole grid. a stroke is two columns wide, so a line that wobbles by a cell from being turned upright still
+    // counts but a slanted one doesn't
+    pub fn stroke_down(&self, left: f64, right: f64) -> f64 {
+        let (Some(columns), Some(rows)) = (self.columns(), self.rows()) else {
+            return 0.0;
+        };
+
+        let covered = (columns.0..=columns.1)
+            .filter(|x| (left..=right).contains(&fraction(*x, columns)))
+            .map(|x| {
+                (rows.0..=rows.1)
+                    .filter(|y| (x..=x + 1).any(|x| self.get(x, *y)))
+                    .count()
+            })
+            .max()
+            .unwrap_or_default();
+
+        covered as f64 / f64::from(rows.1 - rows.0 + 1)
+    }
+
+    // the share of the glyph's columns with a set cell somewhere between top and bottom, given as fractions of the
+    // glyph's height
+    pub fn columns_covered(&self, top: f64, bottom: f64) -> f64 {
+        let (Some(columns), Some(rows)) = (self.columns(), self.rows()) else {
+            return 0.0;
+        };
+
+        let inside = |y: i32| (top..=bottom).contains(&fraction(y, rows));
+        let covered = (columns.0..=columns.1)
+            .filter(|x| (rows.0..=rows.1).any(|y| self.get(*x, y) && inside(y)))
+            .count();
+
+        covered as f64 / f64::from(columns.1 - columns.0 + 1)
+    }
+
+    // the first and last row with anything in them
+    pub fn rows(&self) -> Option<(i32, i32)> {
+        let first = self.rows.iter().position(|i| *i != 0)?;
+        let last = self.rows.iter().rposition(|i| *i != 0)?;
+
+        Some((first as i32, last as i32))
+    }
+
+    // the first and last column with anything in them
+    pub fn columns(&self) -> Option<(i32, i32)> {
+        let all = self.rows.iter().fold(0, |a, i| a | i);
+
+        (all != 0).then(|| {
+            (
+                all.trailing_zeros() as i32,
+                (u16::BITS - 1 - all.leading_zeros()) as i32,
+            )
+        })
+    }
+}
+
+// where a cell sits between first and last, 0 at first and 1 at last
+fn fraction(cell: i32, (first, last): (i32, i32)) -> f64 {
+    if last > first {
+        f64::from(cell - first) / f64::from(last - first)
+    } else {
+        0.5
+    }
+}
+
+// '#' for set cells and '.' for the rest, a row per line
+impl fmt::Display for GlyphBitmap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..SIZE as i32 {
+            let row: String = (0..SIZE as i32)
+                .map(|x| if self.get(x, y) { '#' } else { '.' })
+                .collect();
+            writeln!(f, "{}", row)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for GlyphBitmap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "GlyphBitmap({:016x})", self.fingerprint())
+    }
+}
@@ -0,0 +1,243 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use hts_prog6::{fetch::DEFAULT_SESSION, image::MAX_SCALE};
+
+#[derive(Parser)]
+#[command(
+    name = "hts-prog6",
+    about = "Solver for HackThisSite programming mission 6"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// What to log to stderr, e.g. "debug" or "hts_prog6::segment=trace". Only warnings by default
+    #[arg(long, global = true, env = "HTS_LOG", default_value = "warn")]
+    pub log: String,
+
+    /// Give up if the whole run (fetching, decoding and submitting) takes longer than this many milliseconds
+    #[arg(long, global = true, env = "HTS_DEADLINE_MS")]
+    pub deadline: Option<u64>,
+
+    /// Print how long every stage took to stderr when done
+    #[arg(long, global = true)]
+    pub timings: bool,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Download the mission page and save it to disk
+    Fetch(FetchArgs),
+    /// Decode the characters drawn on a page
+    Decode(DecodeArgs),
+    /// Write out what the decoder sees, for debugging
+    Render(RenderArgs),
+    /// Send an answer to the mission. Decodes a fresh page if no answer is given
+    Submit(SubmitArgs),
+    /// Show everything the decoder knows about a single character
+    Inspect(InspectArgs),
+    /// Train the learned strategy's model and report how it does on characters it wasn't trained on
+    Train(TrainArgs),
+}
+
+// where the drawing comes from. a saved page, a file with just the drawData numbers, or a fresh download if neither is given
+#[derive(Args)]
+pub struct Source {
+    /// HackThisSite session id used when talking to the site
+    #[arg(long, env = "HTS_SESSION", default_value = DEFAULT_SESSION)]
+    pub session: String,
+
+    /// Saved mission page, comma separated drawData list, or the divs the page drew. Fetches the page if left out
+    #[arg(short, long)]
+    pub input: Option<PathBuf>,
+
+    /// How characters are read
+    #[arg(long, value_enum, default_value_t = Recogniser::Rules)]
+    pub strategy: Recogniser,
+
+    /// Model file the learned strategy reads with
+    #[arg(long, default_value = "model.json")]
+    pub model: PathBuf,
+
+    /// Warn if the page's drawing script differs from src/known_good_script.js. Off until that copy is captured from
+    /// the real page, since until then every real page differs
+    #[arg(long)]
+    pub check_script: bool,
+}
+
+#[derive(Args)]
+pub struct FetchArgs {
+    /// HackThisSite session id used when talking to the site
+    #[arg(long, env = "HTS_SESSION", default_value = DEFAULT_SESSION)]
+    pub session: String,
+
+    /// Where to save the page
+    #[arg(short, long, default_value = "read_me.html")]
+    pub output: PathBuf,
+
+    #[arg(short, long, value_enum, default_value_t = FetchFormat::Html)]
+    pub format: FetchFormat,
+
+    /// Warn if the page's drawing script differs from src/known_good_script.js. Off until that copy is captured from
+    /// the real page, since until then every real page differs
+    #[arg(long)]
+    pub check_script: bool,
+}
+
+#[derive(Args)]
+pub struct DecodeArgs {
+    #[command(flatten)]
+    pub source: Source,
+
+    /// Write the result here instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    #[arg(short, long, value_enum, default_value_t = DecodeFormat::Text)]
+    pub format: DecodeFormat,
+}
+
+#[derive(Args)]
+pub struct RenderArgs {
+    #[command(flatten)]
+    pub source: Source,
+
+    /// Write the render here instead of stdout. For images this is the directory they are written to
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    #[arg(short, long, value_enum, default_value_t = RenderFormat::Css)]
+    pub format: RenderFormat,
+
+    /// Size of one page pixel in the image
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(i32).range(1..=i64::from(MAX_SCALE)))]
+    pub scale: i32,
+
+    /// Give every character its own colour
+    #[arg(long)]
+    pub colour: bool,
+
+    /// Label every character with its reading order index
+    #[arg(long)]
+    pub index_labels: bool,
+
+    /// Label every character with what it was read as
+    #[arg(long)]
+    pub char_labels: bool,
+
+    /// Also write a cropped image of every character
+    #[arg(long)]
+    pub glyphs: bool,
+}
+
+#[derive(Args)]
+pub struct SubmitArgs {
+    #[command(flatten)]
+    pub source: Source,
+
+    /// The answer to send. Decoded from the source if left out
+    #[arg(short, long)]
+    pub answer: Option<String>,
+
+    /// Save the server's response here
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    #[arg(short, long, value_enum, default_value_t = SubmitFormat::Status)]
+    pub format: SubmitFormat,
+}
+
+#[derive(Args)]
+pub struct InspectArgs {
+    #[command(flatten)]
+    pub source: Source,
+
+    /// Reading order index of the character, 0 is the top-most one
+    pub index: usize,
+
+    /// Write the report here instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    #[arg(short, long, value_enum, default_value_t = InspectFormat::Text)]
+    pub format: InspectFormat,
+}
+
+#[derive(Args)]
+pub struct TrainArgs {
+    /// Where to write the model
+    #[arg(short, long, default_value = "model.json")]
+    pub output: PathBuf,
+
+    /// How many synthetic pages to take characters from
+    #[arg(long, default_value_t = 8)]
+    pub synthetic: u64,
+
+    /// A real page and a file with the answer it should decode to, as PAGE=ANSWER. Can be given more than once
+    #[arg(long)]
+    pub corrected: Vec<String>,
+
+    /// Share of the characters kept back to test the model on rather than trained on
+    #[arg(long, default_value_t = 0.2)]
+    pub holdout: f64,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Recogniser {
+    /// Hand written rules asked of every character turned upright by its place on the spiral
+    Rules,
+    /// Nearest reference character by shape alone, however the character is turned
+    Moments,
+    /// The classifier in the --model file, see the train command
+    Learned,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum FetchFormat {
+    /// The page exactly as the server sent it
+    Html,
+    /// Only the drawData numbers
+    DrawData,
+    /// The drawing script with drawData left out, ready to use as src/known_good_script.js
+    Script,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum DecodeFormat {
+    /// Just the decoded string
+    Text,
+    /// One line per character with its index, angle and section
+    Verbose,
+    /// Every character's geometry, id, confidence and alternatives, plus the ring and how long each stage took
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum RenderFormat {
+    /// One "left:..px;top:..px;width:..px;height:..px;" line per rect, grouped by character
+    Css,
+    /// PNG images
+    Png,
+    /// Binary PPM images
+    Ppm,
+    /// Vector drawing with every character boxed and labelled, and the reading order drawn as arrows
+    Svg,
+    /// Single self-contained HTML page rebuilding the drawing as divs, with every character's decision path on hover
+    Html,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SubmitFormat {
+    /// The answer that was sent
+    Status,
+    /// The server's response page
+    Html,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum InspectFormat {
+    /// Human readable report
+    Text,
+}
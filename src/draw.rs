@@ -0,0 +1,445 @@
+use std::{error::Error, f64::consts::PI, fmt};
+
+use tracing::debug;
+
+// (left, top, width, height) of one of the green divs the page draws
+pub type Rect = (i32, i32, i32, i32);
+
+// anything bigger than this isn't something the mission would draw, it's garbage that would eat all the memory
+const MAX_ARC_SWEEP: i32 = 360 * 4;
+const MAX_LINE_SPAN: u32 = 10_000;
+
+// one call the page's loop makes while walking drawData
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DrawCommand {
+    Arc {
+        x: i32,
+        y: i32,
+        r: i32,
+        s: i32,
+        e: i32,
+    },
+    Line {
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+    },
+}
+
+impl DrawCommand {
+    pub fn is_line(&self) -> bool {
+        matches!(self, DrawCommand::Line { .. })
+    }
+
+    pub fn rasterize(&self) -> Result<Vec<Rect>, RasterizationFault> {
+        match *self {
+            DrawCommand::Arc { x, y, r, s, e } => get_curve_coordinates(x, y, r, s, e),
+            DrawCommand::Line { x1, y1, x2, y2 } => get_line_coordinates(x1, y1, x2, y2),
+        }
+    }
+}
+
+// the page's drawData couldn't be read. offsets are bytes into whatever text was being read
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExtractionError {
+    NoDrawData,
+    Unterminated {
+        offset: usize,
+    },
+    BadNumber {
+        offset: usize,
+        index: usize,
+        text: String,
+    },
+    // a div's style is missing one of left, top, width and height, or one isn't a whole number of px
+    BadDiv {
+        offset: usize,
+        text: String,
+    },
+    // drawData ends part way through a command. offset is the position in drawData the leftover values start at
+    Truncated {
+        offset: usize,
+        leftover: Vec<i32>,
+    },
+}
+
+impl fmt::Display for ExtractionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExtractionError::NoDrawData => write!(f, "no \"new Array(\" on the page"),
+            ExtractionError::Unterminated { offset } => {
+                write!(
+                    f,
+                    "the drawData array starting at byte {} never ends",
+                    offset
+                )
+            }
+            ExtractionError::BadNumber {
+                offset,
+                index,
+                text,
+            } => write!(
+                f,
+                "value {} of drawData ({:?} at byte {}) isn't a number",
+                index, text, offset
+            ),
+            ExtractionError::BadDiv { offset, text } => write!(
+                f,
+                "the div at byte {} ({:?}) doesn't give left, top, width and height in px",
+                offset, text
+            ),
+            ExtractionError::Truncated { offset, leftover } => write!(
+                f,
+                "drawData ends part way through a command, {:?} is left over from value {}",
+                leftover, offset
+            ),
+        }
+    }
+}
+
+impl Error for ExtractionError {}
+
+// why a single command couldn't be drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RasterizationFault {
+    SweepTooLarge(i32),
+    LineTooLong(u32),
+}
+
+impl fmt::Display for RasterizationFault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RasterizationFault::SweepTooLarge(e) => {
+                write!(f, "arc sweeps {} degrees, more than {}", e, MAX_ARC_SWEEP)
+            }
+            RasterizationFault::LineTooLong(span) => {
+                write!(f, "line spans {} pixels, more than {}", span, MAX_LINE_SPAN)
+            }
+        }
+    }
+}
+
+impl Error for RasterizationFault {}
+
+// a command in drawData couldn't be drawn. index is the command's position in the page's loop, not the value's
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RasterizationError {
+    pub index: usize,
+    pub command: DrawCommand,
+    pub fault: RasterizationFault,
+}
+
+impl fmt::Display for RasterizationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "command {} ({:?}): {}",
+            self.index, self.command, self.fault
+        )
+    }
+}
+
+impl Error for RasterizationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.fault)
+    }
+}
+
+// pulls the numbers out of "new Array(...)" in the page source
+pub fn scrape_draw_data(res_body: &str) -> Result<Vec<i32>, ExtractionError> {
+    // {index} is 192. index 198 is where the first number of the array can be found
+    let start_index = res_body.find("Array").ok_or(ExtractionError::NoDrawData)? + 6;
+    debug!(offset = start_index, "found the first number");
+
+    let end_index_plus_one =
+        res_body[start_index..]
+            .find(");")
+            .ok_or(ExtractionError::Unterminated {
+                offset: start_index,
+            })?
+            + start_index;
+    debug!(offset = end_index_plus_one, "found the last number");
+
+    parse_numbers(&res_body[start_index..end_index_plus_one], start_index)
+}
+
+// get drawData array. collect all the values into a vector
+pub fn parse_draw_data(draw_data_string: &str) -> Result<Vec<i32>, ExtractionError> {
+    parse_numbers(draw_data_string, 0)
+}
+
+// reads rects back out of divs, "left:1px;top:2px;width:3px;height:4px;" each, the way the page's js styles them and
+// the way inspect prints them. whatever's around the styles (tags, quotes, other properties) is skipped
+pub fn parse_divs(text: &str) -> Result<Vec<Rect>, ExtractionError> {
+    let mut rects = Vec::new();
+
+    for (offset, _) in text.match_indices("left:") {
+        let style = &text[offset..];
+        let style = &style[..style.find(['"', '\'', '\n', '>']).unwrap_or(style.len())];
+
+        let value = |name: &str| -> Option<i32> {
+            let start = style.find(&format!("{}:", name))? + name.len() + 1;
+            let value = &style[start..];
+            value[..value.find("px")?].trim().parse().ok()
+        };
+
+        match (value("left"), value("top"), value("width"), value("height")) {
+            (Some(left), Some(top), Some(width), Some(height)) => {
+                rects.push((left, top, width, height))
+            }
+            _ => {
+                return Err(ExtractionError::BadDiv {
+                    offset,
+                    text: style.to_string(),
+                })
+            }
+        }
+    }
+
+    Ok(rects)
+}
+
+// base is where text starts in the page, so errors point at the right byte
+fn parse_numbers(text: &str, base: usize) -> Result<Vec<i32>, ExtractionError> {
+    let mut offset = base;
+    let mut numbers = Vec::new();
+
+    for (index, num_str) in text.split(',').enumerate() {
+        let number = num_str
+            .trim()
+            .parse::<i32>()
+            .map_err(|_| ExtractionError::BadNumber {
+                offset: offset + (num_str.len() - num_str.trim_start().len()),
+                index,
+                text: num_str.trim().to_string(),
+            })?;
+
+        numbers.push(number);
+        offset += num_str.len() + 1;
+    }
+
+    Ok(numbers)
+}
+
+// same as the loop in the page: if the third value is 10 or more it's a line (4 values), otherwise it's an arc (5 values).
+// values left at the end that don't make a whole command mean the array was cut short, so they're an error rather
+// than something to quietly skip
+pub fn commands(draw_data_array: &[i32]) -> Result<Vec<DrawCommand>, ExtractionError> {
+    let mut commands = Vec::new();
+    let mut i = 0;
+
+    while i < draw_data_array.len() {
+        match draw_data_array[i..] {
+            [x1, y1, x2, y2, ..] if x2 >= 10 => {
+                commands.push(DrawCommand::Line { x1, y1, x2, y2 });
+                i += 4;
+            }
+            [x, y, r, s, e, ..] if r < 10 => {
+                commands.push(DrawCommand::Arc { x, y, r, s, e });
+                i += 5;
+            }
+            _ => {
+                return Err(ExtractionError::Truncated {
+                    offset: i,
+                    leftover: draw_data_array[i..].to_vec(),
+                })
+            }
+        }
+    }
+
+    Ok(commands)
+}
+
+// translate the javascript from the source code into rust in order to grab all the x and y coordinates of the green-fill div containers
+// returns (curves, lines)
+pub fn rasterize(commands: &[DrawCommand]) -> Result<(Vec<Rect>, Vec<Rect>), RasterizationError> {
+    // curve refers to arc
+    // the tuple has a format of (left, top, width, height) where (left, top) are positions in the xy plane
+    let mut curves: Vec<Rect> = Vec::new();
+
+    // Unlike the arcs, the lines don't have a constant width and height of one.
+    let mut lines: Vec<Rect> = Vec::new();
+
+    for (index, command) in commands.iter().enumerate() {
+        let mut rects = command.rasterize().map_err(|fault| RasterizationError {
+            index,
+            command: *command,
+            fault,
+        })?;
+
+        if command.is_line() {
+            lines.append(&mut rects);
+        } else {
+            curves.append(&mut rects);
+        }
+    }
+
+    Ok((curves, lines))
+}
+
+// (left, top, right, bottom) of everything in rects
+pub fn bounds(rects: &[Rect]) -> Option<(i32, i32, i32, i32)> {
+    Some((
+        rects.iter().map(|i| i.0).min()?,
+        rects.iter().map(|i| i.1).min()?,
+        rects.iter().map(|i| i.0 + i.2).max()?,
+        rects.iter().map(|i| i.1 + i.3).max()?,
+    ))
+}
+
+// middle of a set of rects (usually one character), weighted by how many pixels each rect covers
+pub fn centroid(rects: &[Rect]) -> Option<(f64, f64)> {
+    let area: f64 = rects.iter().map(|i| f64::from(i.2 * i.3)).sum();
+
+    if area == 0.0 {
+        return None;
+    }
+
+    let x: f64 = rects
+        .iter()
+        .map(|i| (f64::from(i.0) + f64::from(i.2) / 2.0) * f64::from(i.2 * i.3))
+        .sum();
+    let y: f64 = rects
+        .iter()
+        .map(|i| (f64::from(i.1) + f64::from(i.3) / 2.0) * f64::from(i.2 * i.3))
+        .sum();
+
+    Some((x / area, y / area))
+}
+
+// ---------------------translated the functions from the js in the source code into rust-----------------
+
+type CurveCoordinatesAndDeets = Result<Vec<(i32, i32, i32, i32)>, RasterizationFault>;
+
+// drawArc func
+pub fn get_curve_coordinates(x: i32, y: i32, r: i32, s: i32, e: i32) -> CurveCoordinatesAndDeets {
+    if e > MAX_ARC_SWEEP {
+        return Err(RasterizationFault::SweepTooLarge(e));
+    }
+
+    // convert to f64 in order to perform the trig calculations.
+    let (x, y, r, s, e) = (
+        f64::from(x),
+        f64::from(y),
+        f64::from(r),
+        f64::from(s),
+        f64::from(e),
+    );
+
+    let mut curve_deets: Vec<(i32, i32, i32, i32)> = Vec::new();
+
+    let to_radian: f64 = PI / 180.0;
+    let mut xx_last: f64 = -1.0;
+    let mut yy_last: f64 = -1.0;
+    let mut ss = s;
+
+    while ss <= s + e {
+        // let ss_to_rad = ss * to_radian;
+        let xx = (x + r * (ss * to_radian).cos()).round();
+        let yy = (y - r * (ss * to_radian).sin()).round();
+
+        if xx != xx_last || yy != yy_last {
+            curve_deets.push((xx as i32, yy as i32, 1, 1));
+            xx_last = xx;
+            yy_last = yy;
+        }
+
+        ss += 8.0;
+    }
+
+    Ok(curve_deets)
+}
+
+type LineCoordinatesAndDeets = Result<Vec<(i32, i32, i32, i32)>, RasterizationFault>;
+
+// drawLine func
+pub fn get_line_coordinates(x1_i: i32, y1_i: i32, x2_i: i32, y2_i: i32) -> LineCoordinatesAndDeets {
+    let span = x1_i.abs_diff(x2_i).max(y1_i.abs_diff(y2_i));
+    if span > MAX_LINE_SPAN {
+        return Err(RasterizationFault::LineTooLong(span));
+    }
+
+    let mut line_deets: Vec<(i32, i32, i32, i32)> = Vec::new();
+
+    let (mut x1, mut y1, mut x2, mut y2) = (x1_i, y1_i, x2_i, y2_i);
+
+    if x1 > x2 {
+        let x2_initial = x2;
+        let y2_initial = y2;
+
+        x2 = x1;
+        y2 = y1;
+        x1 = x2_initial;
+        y1 = y2_initial;
+    }
+
+    let mut dx = x2 - x1;
+    let mut dy = (y2 - y1).abs();
+    let mut x = x1;
+    let mut y = y1;
+    let y_inc = if y1 > y2 { -1 } else { 1 };
+
+    if dx >= dy {
+        let mut x_old = x;
+        let pr = dy << 1;
+        let pru = pr - (dx << 1);
+        let mut p = pr - dx;
+
+        while dx > 0 {
+            x += 1;
+            if p > 0 {
+                line_deets.push((x_old, y, x - x_old, 1)); // x_old, y, x - x_old, 1
+                x_old = x;
+                y += y_inc;
+                p += pru;
+            } else {
+                p += pr;
+            }
+            dx -= 1;
+        }
+        line_deets.push((x_old, y, x2 - x_old + 1, 1)); // x_old, y, x2 - x_old + 1, 1
+    } else {
+        let pr = dx << 1;
+        let mut y_old = y;
+        let pru = pr - (dy << 1);
+        let mut p = pr - dy;
+
+        if y2 <= y1 {
+            while dy > 0 {
+                if p > 0 {
+                    line_deets.push((x, y, 1, y_old - y + 1)); // x++, y, 1, y_old - y + 1
+                    x += 1;
+                    y_old = y;
+                    y += y_inc;
+                    p += pru;
+                } else {
+                    y += y_inc;
+                    p += pr;
+                }
+
+                dy -= 1;
+            }
+
+            line_deets.push((x2, y2, 1, y_old - y2 + 1)); // x2, y2, 1, y_old - y2 + 1
+        } else {
+            while dy > 0 {
+                y += y_inc;
+                if p > 0 {
+                    line_deets.push((x, y_old, 1, y - y_old)); // x++, y_old, 1, y - y_old
+                    x += 1;
+                    y_old = y;
+                    p += pru;
+                } else {
+                    p += pr;
+                }
+
+                dy -= 1;
+            }
+
+            line_deets.push((x2, y_old, 1, y2 - y_old + 1)); // x2, y_old, 1, y2 - y_old + 1
+        }
+    }
+
+    Ok(line_deets)
+}
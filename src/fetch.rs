@@ -0,0 +1,97 @@
+use std::{error::Error, fmt};
+
+use reqwest::header::{HeaderMap, HeaderValue, COOKIE, REFERER};
+
+pub const PAGE_URL: &str = "https://www.hackthissite.org/missions/prog/6/image/";
+pub const SUBMIT_URL: &str = "https://www.hackthissite.org/missions/prog/6/index.php";
+
+// the HackThisSite session id. the mission page is tied to whoever is logged in
+pub const DEFAULT_SESSION: &str = "5eku78f0d06s302455vep962d1";
+
+#[derive(Debug)]
+pub enum NetError {
+    // the session id has characters that can't go in a cookie
+    BadSession(String),
+    Request {
+        url: &'static str,
+        source: reqwest::Error,
+    },
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetError::BadSession(session) => {
+                write!(f, "{:?} can't be used as a session id", session)
+            }
+            NetError::Request { url, source } => write!(f, "request to {} failed: {}", url, source),
+        }
+    }
+}
+
+impl Error for NetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NetError::BadSession(_) => None,
+            NetError::Request { source, .. } => Some(source),
+        }
+    }
+}
+
+fn headers(session: &str) -> Result<HeaderMap, NetError> {
+    let mut headers = HeaderMap::new();
+
+    // insert cookies manually
+    headers.insert(
+        COOKIE,
+        format!("HackThisSite={}", session)
+            .parse()
+            .map_err(|_| NetError::BadSession(session.to_string()))?,
+    );
+
+    Ok(headers)
+}
+
+pub async fn fetch_page(session: &str) -> Result<String, NetError> {
+    let client = reqwest::Client::new();
+    let failed = |source| NetError::Request {
+        url: PAGE_URL,
+        source,
+    };
+
+    client
+        .get(PAGE_URL)
+        .headers(headers(session)?)
+        .send()
+        .await
+        .and_then(|i| i.error_for_status())
+        .map_err(failed)?
+        .text()
+        .await
+        .map_err(failed)
+}
+
+// posts the answer the same way the mission's form does. returns the page the server sends back. an error status from
+// the site is a failed request, not an answer that was looked at
+pub async fn submit(session: &str, answer: &str) -> Result<String, NetError> {
+    let client = reqwest::Client::new();
+    let failed = |source| NetError::Request {
+        url: SUBMIT_URL,
+        source,
+    };
+
+    let mut headers = headers(session)?;
+    headers.insert(REFERER, HeaderValue::from_static(PAGE_URL));
+
+    client
+        .post(SUBMIT_URL)
+        .headers(headers)
+        .form(&[("solution", answer)])
+        .send()
+        .await
+        .and_then(|i| i.error_for_status())
+        .map_err(failed)?
+        .text()
+        .await
+        .map_err(failed)
+}
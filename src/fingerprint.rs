@@ -0,0 +1,394 @@
+// the decoder only works as long as the page draws things exactly the way main.rs assumes (8 degree arc steps, the ">= 10" check
+// that tells lines from arcs, the div geometry of drawLine). this compares the page's drawing script against a known good
+// copy, so we find out the page changed before we decode garbage. see the top of known_good_script.js for where that copy
+// came from, and fetch --format script for capturing a new one.
+
+use std::fmt;
+
+use crate::page::{script_blocks, split_functions};
+
+pub const KNOWN_GOOD_SCRIPT: &str = include_str!("known_good_script.js");
+
+// name used for everything in the script that isn't inside a function declaration
+const TOP_LEVEL: &str = "<top level>";
+
+// one function (or the top level code) reduced to its statements with comments and formatting removed
+#[derive(Debug, Clone)]
+pub struct Piece {
+    pub name: String,
+    pub statements: Vec<String>,
+    pub hash: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Fingerprint {
+    pub pieces: Vec<Piece>,
+}
+
+impl Fingerprint {
+    pub fn known_good() -> Fingerprint {
+        Fingerprint::of_scripts(&[KNOWN_GOOD_SCRIPT])
+    }
+
+    // only the script blocks that have something to do with drawing are looked at, anything else on the page is ignored
+    pub fn of_page(page: &str) -> Fingerprint {
+        Fingerprint::of_scripts(&drawing_scripts(page))
+    }
+
+    fn of_scripts(scripts: &[&str]) -> Fingerprint {
+        let mut pieces = Vec::new();
+        let mut top_level = Vec::new();
+
+        for script in scripts {
+            let (functions, rest) = split_functions(script);
+
+            for (name, source) in functions {
+                pieces.push(Piece::new(name, normalise(&source)));
+            }
+
+            top_level.append(&mut normalise(&blank_draw_data(&rest)));
+        }
+
+        pieces.push(Piece::new(TOP_LEVEL.to_owned(), top_level));
+
+        Fingerprint { pieces }
+    }
+
+    // hash of all the pieces together, handy for printing one value
+    pub fn combined(&self) -> u64 {
+        let mut hash = FNV_OFFSET;
+
+        for i in self.pieces.iter() {
+            hash = fnv1a(hash, i.name.as_bytes());
+            hash = fnv1a(hash, &i.hash.to_le_bytes());
+        }
+
+        hash
+    }
+
+    fn get(&self, name: &str) -> Option<&Piece> {
+        self.pieces.iter().find(|i| i.name == name)
+    }
+}
+
+impl Piece {
+    fn new(name: String, statements: Vec<String>) -> Piece {
+        let mut hash = FNV_OFFSET;
+
+        for i in statements.iter() {
+            hash = fnv1a(hash, i.as_bytes());
+            hash = fnv1a(hash, b"\n");
+        }
+
+        Piece {
+            name,
+            statements,
+            hash,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ScriptChange {
+    pub name: String,
+    pub kind: ChangeKind,
+    pub expected: Option<u64>,
+    pub found: Option<u64>,
+    pub diff: Vec<DiffLine>,
+}
+
+// the "page format changed" diagnostic. one entry for every function that was added, removed or edited
+#[derive(Debug, Clone)]
+pub struct PageFormatChanged {
+    pub expected: u64,
+    pub found: u64,
+    pub changes: Vec<ScriptChange>,
+}
+
+impl fmt::Display for PageFormatChanged {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "page format changed: drawing script fingerprint is {:016x}, expected {:016x}",
+            self.found, self.expected
+        )?;
+
+        for change in self.changes.iter() {
+            let hash = |h: Option<u64>| match h {
+                Some(h) => format!("{:016x}", h),
+                None => String::from("-"),
+            };
+
+            writeln!(
+                f,
+                "{:?} {} (expected {}, found {})",
+                change.kind,
+                change.name,
+                hash(change.expected),
+                hash(change.found)
+            )?;
+
+            for line in change.diff.iter() {
+                match line {
+                    DiffLine::Same(s) => writeln!(f, "    {}", s)?,
+                    DiffLine::Removed(s) => writeln!(f, "  - {}", s)?,
+                    DiffLine::Added(s) => writeln!(f, "  + {}", s)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for PageFormatChanged {}
+
+// compares the page's drawing script against the known good one
+pub fn check(page: &str) -> Result<Fingerprint, PageFormatChanged> {
+    let known = Fingerprint::known_good();
+    let found = Fingerprint::of_page(page);
+
+    let mut changes = Vec::new();
+
+    for expected in known.pieces.iter() {
+        match found.get(&expected.name) {
+            Some(piece) if piece.hash == expected.hash => {}
+            Some(piece) => changes.push(ScriptChange {
+                name: expected.name.clone(),
+                kind: ChangeKind::Modified,
+                expected: Some(expected.hash),
+                found: Some(piece.hash),
+                diff: diff(&expected.statements, &piece.statements),
+            }),
+            None => changes.push(ScriptChange {
+                name: expected.name.clone(),
+                kind: ChangeKind::Removed,
+                expected: Some(expected.hash),
+                found: None,
+                diff: diff(&expected.statements, &[]),
+            }),
+        }
+    }
+
+    for piece in found.pieces.iter() {
+        if known.get(&piece.name).is_none() {
+            changes.push(ScriptChange {
+                name: piece.name.clone(),
+                kind: ChangeKind::Added,
+                expected: None,
+                found: Some(piece.hash),
+                diff: diff(&[], &piece.statements),
+            });
+        }
+    }
+
+    if changes.is_empty() {
+        Ok(found)
+    } else {
+        Err(PageFormatChanged {
+            expected: known.combined(),
+            found: found.combined(),
+            changes,
+        })
+    }
+}
+
+// the page's drawing script with the drawData values left out, laid out the way known_good_script.js keeps it
+pub fn drawing_script(page: &str) -> String {
+    let scripts: Vec<String> = drawing_scripts(page)
+        .into_iter()
+        .map(|i| blank_draw_data(i.trim()))
+        .collect();
+
+    scripts.join("\n\n") + "\n"
+}
+
+fn drawing_scripts(page: &str) -> Vec<&str> {
+    script_blocks(page)
+        .into_iter()
+        .filter(|i| i.contains("drawData") || i.contains("drawArc") || i.contains("drawLine"))
+        .collect()
+}
+
+// drawData changes on every page load, so its values are left out of the fingerprint
+fn blank_draw_data(source: &str) -> String {
+    if let Some(start) = source.find("Array(") {
+        let start = start + "Array(".len();
+
+        if let Some(len) = source[start..].find(')') {
+            return format!("{}...{}", &source[..start], &source[start + len..]);
+        }
+    }
+
+    source.to_owned()
+}
+
+// strips comments and whitespace that doesn't separate two words, then breaks the code up after every ; { and }
+// so reformatting the page doesn't count as a change but any edit to the code does
+fn normalise(source: &str) -> Vec<String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut pending_space = false;
+    let mut i = 0;
+
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            pending_space = true;
+            continue;
+        }
+
+        if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                i += 1;
+            }
+            i += 2;
+            pending_space = true;
+            continue;
+        }
+
+        if c.is_whitespace() {
+            pending_space = true;
+            i += 1;
+            continue;
+        }
+
+        if pending_space && current.chars().last().is_some_and(is_word) && is_word(c) {
+            current.push(' ');
+        }
+        pending_space = false;
+
+        if c == '"' || c == '\'' {
+            current.push(c);
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    current.push(chars[i]);
+                    i += 1;
+                }
+                current.push(chars[i]);
+                i += 1;
+            }
+            if i < chars.len() {
+                current.push(c);
+            }
+            i += 1;
+            continue;
+        }
+
+        current.push(c);
+
+        if c == ';' || c == '{' || c == '}' {
+            statements.push(std::mem::take(&mut current));
+        }
+
+        i += 1;
+    }
+
+    if !current.is_empty() {
+        statements.push(current);
+    }
+
+    statements
+}
+
+// longest common subsequence diff, statements are short enough that the quadratic table is fine
+fn diff(old: &[String], new: &[String]) -> Vec<DiffLine> {
+    let (n, m) = (old.len(), new.len());
+    let mut table = vec![vec![0usize; m + 1]; n + 1];
+
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if old[i] == new[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].clone()));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || table[i + 1][j] >= table[i][j + 1]) {
+            lines.push(DiffLine::Removed(old[i].clone()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j].clone()));
+            j += 1;
+        }
+    }
+
+    trim_context(lines)
+}
+
+// only keep a couple of unchanged statements around each change
+fn trim_context(lines: Vec<DiffLine>) -> Vec<DiffLine> {
+    const CONTEXT: usize = 2;
+
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !matches!(l, DiffLine::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut trimmed = Vec::new();
+    let mut skipped = false;
+
+    for (i, line) in lines.into_iter().enumerate() {
+        let near_change = changed
+            .iter()
+            .any(|c| i + CONTEXT >= *c && i <= c + CONTEXT);
+
+        if near_change {
+            trimmed.push(line);
+            skipped = false;
+        } else if !skipped {
+            trimmed.push(DiffLine::Same(String::from("...")));
+            skipped = true;
+        }
+    }
+
+    trimmed
+}
+
+pub(crate) const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+// fnv-1a. std's hasher isn't guaranteed to give the same value across rust versions, this is
+pub(crate) fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for b in bytes {
+        hash ^= u64::from(*b);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+
+    hash
+}
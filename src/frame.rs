@@ -0,0 +1,77 @@
+// a glyph's own coordinates. every character is drawn upright and then turned anticlockwise by its angle, so rather
+// than asking which quarter of the circle a glyph is in and flipping every rule to match, turn the question round
+// into the glyph's frame: x goes to the glyph's right and y down towards its bottom, however far round it is
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::{
+    draw::Rect,
+    transform::{Affine, Resampling},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Frame {
+    // where (0, 0) of the glyph's coordinates is on the page
+    pub origin: (f64, f64),
+    // degrees anticlockwise the glyph is turned from upright
+    pub rotation: f64,
+}
+
+impl Frame {
+    pub fn new(origin: (f64, f64), rotation: f64) -> Frame {
+        Frame { origin, rotation }
+    }
+
+    // the same frame turned further anticlockwise
+    pub fn turned(&self, degrees: f64) -> Frame {
+        Frame::new(self.origin, (self.rotation + degrees).rem_euclid(360.0))
+    }
+
+    // the direction on the page the top of the glyph points in, one pixel long. y grows downwards on the page
+    pub fn up(&self) -> (f64, f64) {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        (-sin, -cos)
+    }
+
+    // the direction on the page the glyph's right points in
+    pub fn right(&self) -> (f64, f64) {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        (cos, -sin)
+    }
+
+    // page coordinates to the glyph's
+    pub fn to_local_transform(&self) -> Affine {
+        Affine::translation(-self.origin.0, -self.origin.1).then(&Affine::rotation(-self.rotation))
+    }
+
+    // the glyph's coordinates to the page's
+    pub fn to_canvas_transform(&self) -> Affine {
+        Affine::rotation(self.rotation).then(&Affine::translation(self.origin.0, self.origin.1))
+    }
+
+    // a point on the page in the glyph's coordinates
+    pub fn to_local(&self, point: (f64, f64)) -> (f64, f64) {
+        self.to_local_transform().apply(point)
+    }
+
+    // a point in the glyph's coordinates on the page
+    pub fn to_canvas(&self, point: (f64, f64)) -> (f64, f64) {
+        self.to_canvas_transform().apply(point)
+    }
+
+    // the middle of a rect in the glyph's coordinates
+    pub fn rect_to_local(&self, rect: &Rect) -> (f64, f64) {
+        self.to_local((
+            f64::from(rect.0) + f64::from(rect.2) / 2.0,
+            f64::from(rect.1) + f64::from(rect.3) / 2.0,
+        ))
+    }
+
+    // the pixels turned upright, keyed by where they land in whole pixels of the glyph's coordinates
+    pub fn upright<T: Copy>(&self, pixels: &HashMap<(i32, i32), T>) -> HashMap<(i32, i32), T> {
+        self.to_local_transform()
+            .apply_pixels(pixels, Resampling::Nearest)
+    }
+}
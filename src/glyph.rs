@@ -0,0 +1,101 @@
+// one character on the page and everything the pipeline has worked out about it. segmentation makes them, ordering
+// puts them in place, recognition reads them, and reports, renders and submitting all read them back from here
+
+use std::collections::HashMap;
+
+use crate::{
+    analyze::Recognition,
+    draw::{bounds, centroid, DrawCommand, Rect},
+    frame::Frame,
+    segment::{position, Section},
+    spiral::Placement,
+};
+
+#[derive(Debug, Clone)]
+pub struct Glyph {
+    pub rects: Vec<Rect>,
+    // (left, top, width, height)
+    pub bounding_box: (i32, i32, i32, i32),
+    pub centroid: (f64, f64),
+    // reading order
+    pub index: i32,
+    // the angle its place in the reading order says it's turned by
+    pub angle: i32,
+    pub section: Section,
+    // its own coordinates, centred on the centroid and turned by angle
+    pub frame: Frame,
+    // where it actually sits on the spiral, once ordering has fitted one
+    pub placement: Option<Placement>,
+    // every draw command that drew at least one of its rects
+    pub commands: Vec<DrawCommand>,
+    // filled in by recognition
+    pub recognition: Option<Recognition>,
+}
+
+impl Glyph {
+    pub fn new(rects: Vec<Rect>, index: i32) -> Glyph {
+        let (left, top, right, bottom) = bounds(&rects).unwrap_or((0, 0, 0, 0));
+        let centre = centroid(&rects).unwrap_or((f64::from(left), f64::from(top)));
+        let (angle, section) = position(index);
+
+        Glyph {
+            rects,
+            bounding_box: (left, top, right - left, bottom - top),
+            centroid: centre,
+            index,
+            angle,
+            section,
+            frame: Frame::new(centre, f64::from(angle)),
+            placement: None,
+            commands: Vec::new(),
+            recognition: None,
+        }
+    }
+
+    // moves it to index in the reading order, along with the angle, section and frame that go with it
+    pub fn move_to(&mut self, index: i32) {
+        self.index = index;
+        (self.angle, self.section) = position(index);
+        self.frame.rotation = f64::from(self.angle);
+    }
+
+    // the angle it sits at around the spiral's centre, anticlockwise from the top
+    pub fn measured_angle(&self) -> Option<f64> {
+        self.placement.map(|i| i.angle)
+    }
+
+    // what it was read as, None until recognition has run
+    pub fn value(&self) -> Option<&str> {
+        self.recognition.as_ref().map(|i| i.character.val.as_str())
+    }
+}
+
+// hands every glyph the commands that drew it. a command whose rects ended up in more than one glyph goes to all of
+// them
+pub fn attach_commands(glyphs: &mut [Glyph], commands: &[DrawCommand]) {
+    let owner: HashMap<Rect, usize> = glyphs
+        .iter()
+        .enumerate()
+        .flat_map(|(n, glyph)| glyph.rects.iter().map(move |i| (*i, n)))
+        .collect();
+
+    for command in commands.iter() {
+        let mut drew: Vec<usize> = command
+            .rasterize()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|i| owner.get(i).copied())
+            .collect();
+        drew.sort_unstable();
+        drew.dedup();
+
+        for n in drew {
+            glyphs[n].commands.push(*command);
+        }
+    }
+}
+
+// every glyph's value in reading order. glyphs that haven't been read are left out
+pub fn answer(glyphs: &[Glyph]) -> String {
+    glyphs.iter().filter_map(|i| i.value()).collect()
+}
@@ -0,0 +1,156 @@
+// rebuilds the page's div canvas from our own rects instead of re-running the site's js like read_me.html does.
+// one file, no scripts or external resources, so it can be attached to a bug as is
+
+use std::{collections::HashSet, fmt::Write};
+
+use crate::{
+    draw::{bounds, Rect},
+    glyph::Glyph,
+    image::palette,
+};
+
+const MARGIN: i32 = 10;
+const SCALE: i32 = 2;
+const LEFTOVER: &str = "#5a5a5a";
+
+pub fn report(answer: &str, all_rects: &[Rect], glyphs: &[Glyph]) -> String {
+    let (left, top, right, bottom) = bounds(all_rects).unwrap_or((0, 0, 1, 1));
+    let (left, top) = (left - MARGIN, top - MARGIN);
+    let (width, height) = (right - left + MARGIN, bottom - top + MARGIN);
+
+    let mut out = String::new();
+
+    out.push_str(concat!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>hts-prog6 decode report</title>\n<style>\n",
+        "body { background: #111; color: #ccc; font: 12px monospace; }\n",
+        "#canvas { position: relative; background: #000; transform-origin: 0 0; }\n",
+        "#canvas div div { position: absolute; }\n",
+        ".glyph:hover div { background: #fff !important; }\n",
+        "table { border-collapse: collapse; margin-top: 1em; }\n",
+        "td, th { border: 1px solid #333; padding: 2px 6px; text-align: left; vertical-align: top; }\n",
+        ".low { color: #f66; }\n",
+        "</style>\n</head>\n<body>\n",
+    ));
+
+    writeln!(out, "<h1>decoded: {}</h1>", escape(answer)).unwrap();
+    writeln!(
+        out,
+        "<p>{} characters, {} rects. hover over a character to see what the decoder made of it.</p>",
+        glyphs.len(),
+        all_rects.len()
+    )
+    .unwrap();
+
+    writeln!(
+        out,
+        r#"<div style="width:{}px;height:{}px"><div id="canvas" style="width:{}px;height:{}px;transform:scale({})">"#,
+        width * SCALE,
+        height * SCALE,
+        width,
+        height,
+        SCALE
+    )
+    .unwrap();
+
+    let segmented: HashSet<&Rect> = glyphs.iter().flat_map(|i| i.rects.iter()).collect();
+
+    out.push_str("<div class=\"leftover\" title=\"not part of any character\">\n");
+    for i in all_rects.iter().filter(|i| !segmented.contains(i)) {
+        out.push_str(&div(i, left, top, LEFTOVER));
+    }
+    out.push_str("</div>\n");
+
+    for glyph in glyphs.iter() {
+        let (recognised, confidence) = read_as(glyph);
+        let colour = palette(glyph.index);
+        let colour = format!("#{:02x}{:02x}{:02x}", colour[0], colour[1], colour[2]);
+
+        writeln!(
+            out,
+            r#"<div class="glyph" id="glyph-{}" title="index {} angle {} section {:?} read as {} (confidence {:.2})">"#,
+            glyph.index,
+            glyph.index,
+            glyph.angle,
+            glyph.section,
+            escape(recognised),
+            confidence
+        )
+        .unwrap();
+
+        for i in glyph.rects.iter() {
+            out.push_str(&div(i, left, top, &colour));
+        }
+
+        out.push_str("</div>\n");
+    }
+
+    out.push_str("</div></div>\n");
+
+    out.push_str(
+        "<table>\n<tr><th>index</th><th>angle</th><th>section</th><th>rects</th><th>read as</th><th>confidence</th><th>decision path</th></tr>\n",
+    );
+
+    for glyph in glyphs.iter() {
+        let (recognised, confidence) = read_as(glyph);
+        let steps = glyph
+            .recognition
+            .as_ref()
+            .map(|i| i.path.steps.as_slice())
+            .unwrap_or_default();
+        let class = if confidence < 0.5 {
+            " class=\"low\""
+        } else {
+            ""
+        };
+
+        writeln!(
+            out,
+            r##"<tr{}><td><a href="#glyph-{}">{}</a></td><td>{}</td><td>{:?}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{}</td></tr>"##,
+            class,
+            glyph.index,
+            glyph.index,
+            glyph.angle,
+            glyph.section,
+            glyph.rects.len(),
+            escape(recognised),
+            confidence,
+            steps
+                .iter()
+                .map(|i| escape(i))
+                .collect::<Vec<String>>()
+                .join("<br>")
+        )
+        .unwrap();
+    }
+
+    out.push_str("</table>\n</body>\n</html>\n");
+
+    out
+}
+
+// same geometry as the divs the page writes, moved so the drawing starts near the top left corner
+fn div(rect: &Rect, left: i32, top: i32, colour: &str) -> String {
+    format!(
+        "<div style=\"left:{}px;top:{}px;width:{}px;height:{}px;background:{}\"></div>\n",
+        rect.0 - left,
+        rect.1 - top,
+        rect.2,
+        rect.3,
+        colour
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// what a glyph was read as and how sure the rules were, or "?" for one that was never read
+fn read_as(glyph: &Glyph) -> (&str, f64) {
+    match &glyph.recognition {
+        Some(recognition) => (&recognition.character.val, recognition.confidence),
+        None => ("?", 0.0),
+    }
+}
@@ -0,0 +1,352 @@
+// turns the rects back into a picture so a misread character can be spotted by eye instead of pasting divs into a browser
+
+use std::{collections::HashSet, error::Error, fmt};
+
+use crate::{
+    draw::{bounds, Rect},
+    glyph::Glyph,
+};
+
+pub type Colour = [u8; 3];
+
+const BACKGROUND: Colour = [0, 0, 0];
+// the colour the page draws with
+const GREEN: Colour = [0, 255, 0];
+// rects that didn't end up in any character
+const LEFTOVER: Colour = [90, 90, 90];
+const INDEX_LABEL: Colour = [255, 255, 255];
+const CHAR_LABEL: Colour = [255, 220, 0];
+
+// pixels around a character when it's cropped out on its own
+const CROP_MARGIN: i32 = 3;
+
+// the most --scale can be set to. the real page at this scale is well under MAX_PIXELS
+pub const MAX_SCALE: i32 = 16;
+
+// about 800MB of rgb. anything bigger is a mistake, not a picture anyone wants
+const MAX_PIXELS: usize = 1 << 28;
+
+// width and height are in page pixels, before scaling
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageTooLarge {
+    pub width: i64,
+    pub height: i64,
+    pub scale: i32,
+}
+
+impl fmt::Display for ImageTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "a {}x{} drawing at scale {} is more than {} pixels",
+            self.width, self.height, self.scale, MAX_PIXELS
+        )
+    }
+}
+
+impl Error for ImageTooLarge {}
+
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Colour>,
+}
+
+impl Bitmap {
+    pub fn new(width: usize, height: usize) -> Bitmap {
+        Bitmap {
+            width,
+            height,
+            pixels: vec![BACKGROUND; width * height],
+        }
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, colour: Colour) {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            self.pixels[y as usize * self.width + x as usize] = colour;
+        }
+    }
+
+    pub fn fill_rect(&mut self, left: i32, top: i32, width: i32, height: i32, colour: Colour) {
+        for x in left..left + width {
+            for y in top..top + height {
+                self.set(x, y, colour);
+            }
+        }
+    }
+
+    // binary ppm (P6), about the simplest image format there is
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+
+        for i in self.pixels.iter() {
+            bytes.extend_from_slice(i);
+        }
+
+        bytes
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let mut bytes = Vec::new();
+
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, self.width as u32, self.height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(&self.pixels.concat())?;
+        }
+
+        Ok(bytes)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct RenderOptions {
+    // every page pixel becomes a scale x scale square
+    pub scale: i32,
+    // give every character its own colour instead of the page's green
+    pub colour_segments: bool,
+    // write the reading order index above each character
+    pub index_labels: bool,
+    // write what the character was read as next to it
+    pub char_labels: bool,
+}
+
+// the whole drawing. all_rects is everything the page drew, so anything segmentation missed still shows up (in grey)
+pub fn render_canvas(
+    all_rects: &[Rect],
+    segments: &[Glyph],
+    options: RenderOptions,
+) -> Result<Bitmap, ImageTooLarge> {
+    let bounds = bounds(all_rects).unwrap_or((0, 0, 1, 1));
+
+    // labels go above each character, so the top row needs room for them
+    let top_margin = if options.index_labels || options.char_labels {
+        CROP_MARGIN.max(GLYPH_HEIGHT + 1)
+    } else {
+        CROP_MARGIN
+    };
+
+    let (left, top) = (bounds.0 - CROP_MARGIN, bounds.1 - top_margin);
+    let (right, bottom) = (bounds.2 + CROP_MARGIN, bounds.3 + CROP_MARGIN);
+
+    let mut bitmap = scaled(
+        i64::from(right) - i64::from(left),
+        i64::from(bottom) - i64::from(top),
+        options.scale,
+    )?;
+
+    let segmented: HashSet<&Rect> = segments.iter().flat_map(|i| i.rects.iter()).collect();
+
+    for i in all_rects.iter().filter(|i| !segmented.contains(i)) {
+        draw_rect(&mut bitmap, i, left, top, options.scale, LEFTOVER);
+    }
+
+    for segment in segments.iter() {
+        draw_segment(&mut bitmap, segment, left, top, options);
+    }
+
+    Ok(bitmap)
+}
+
+// just the one character, cut out with a small margin
+pub fn render_segment(segment: &Glyph, options: RenderOptions) -> Result<Bitmap, ImageTooLarge> {
+    let bounds = bounds(&segment.rects).unwrap_or((0, 0, 1, 1));
+    let (left, top) = (bounds.0 - CROP_MARGIN, bounds.1 - CROP_MARGIN);
+    let (right, bottom) = (bounds.2 + CROP_MARGIN, bounds.3 + CROP_MARGIN);
+
+    // leave room under the character for the labels
+    let label_room = if options.index_labels || options.char_labels {
+        GLYPH_HEIGHT + 2
+    } else {
+        0
+    };
+
+    let mut bitmap = scaled(
+        i64::from(right) - i64::from(left),
+        i64::from(bottom) - i64::from(top) + i64::from(label_room),
+        options.scale,
+    )?;
+
+    let colour = segment_colour(segment.index, options);
+
+    for i in segment.rects.iter() {
+        draw_rect(&mut bitmap, i, left, top, options.scale, colour);
+    }
+
+    let mut x = 1;
+    let y = bottom - top + 1;
+
+    if options.index_labels {
+        x = draw_text(
+            &mut bitmap,
+            &segment.index.to_string(),
+            x,
+            y,
+            options.scale,
+            INDEX_LABEL,
+        ) + 2;
+    }
+
+    if let (true, Some(text)) = (options.char_labels, segment.value()) {
+        draw_text(&mut bitmap, text, x, y, options.scale, CHAR_LABEL);
+    }
+
+    Ok(bitmap)
+}
+
+// an empty bitmap for width x height page pixels at scale, as long as it comes to no more than MAX_PIXELS. the
+// drawing then fits in an i32 at scale too, so nothing drawn into it can overflow
+fn scaled(width: i64, height: i64, scale: i32) -> Result<Bitmap, ImageTooLarge> {
+    let too_large = ImageTooLarge {
+        width,
+        height,
+        scale,
+    };
+    let side = |i: i64| {
+        i.checked_mul(i64::from(scale))
+            .and_then(|i| usize::try_from(i).ok())
+    };
+
+    match (side(width), side(height)) {
+        (Some(w), Some(h)) if w.checked_mul(h).is_some_and(|i| i <= MAX_PIXELS) => {
+            Ok(Bitmap::new(w, h))
+        }
+        _ => Err(too_large),
+    }
+}
+
+fn draw_segment(bitmap: &mut Bitmap, segment: &Glyph, left: i32, top: i32, options: RenderOptions) {
+    let colour = segment_colour(segment.index, options);
+
+    for i in segment.rects.iter() {
+        draw_rect(bitmap, i, left, top, options.scale, colour);
+    }
+
+    let (seg_left, seg_top, seg_right, _) = match bounds(&segment.rects) {
+        Some(bounds) => bounds,
+        None => return,
+    };
+
+    // labels sit just above the character
+    let y = seg_top - top - GLYPH_HEIGHT - 1;
+
+    if options.index_labels {
+        draw_text(
+            bitmap,
+            &segment.index.to_string(),
+            seg_left - left,
+            y,
+            options.scale,
+            INDEX_LABEL,
+        );
+    }
+
+    if let (true, Some(text)) = (options.char_labels, segment.value()) {
+        draw_text(
+            bitmap,
+            text,
+            seg_right - left + 1,
+            y,
+            options.scale,
+            CHAR_LABEL,
+        );
+    }
+}
+
+fn draw_rect(bitmap: &mut Bitmap, rect: &Rect, left: i32, top: i32, scale: i32, colour: Colour) {
+    bitmap.fill_rect(
+        (rect.0 - left) * scale,
+        (rect.1 - top) * scale,
+        rect.2 * scale,
+        rect.3 * scale,
+        colour,
+    );
+}
+
+fn segment_colour(index: i32, options: RenderOptions) -> Colour {
+    if options.colour_segments {
+        palette(index)
+    } else {
+        GREEN
+    }
+}
+
+// spreads the hues out with the golden angle so neighbouring characters never get similar colours
+pub fn palette(index: i32) -> Colour {
+    let hue = (f64::from(index) * 137.508) % 360.0;
+    let sector = hue / 60.0;
+    let x = 1.0 - (sector % 2.0 - 1.0).abs();
+
+    let (r, g, b) = match sector as i32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+
+    // keep it bright enough to see against the black background
+    let scale = |c: f64| (80.0 + c * 175.0) as u8;
+
+    [scale(r), scale(g), scale(b)]
+}
+
+// ---------------------------------------tiny font for the labels---------------------------------------
+
+const GLYPH_WIDTH: i32 = 3;
+const GLYPH_HEIGHT: i32 = 5;
+
+// 3x5 font covering 0-9 and A-F, which is every index and every character the page can draw.
+// each row is 3 bits, the highest bit is the left-most pixel
+fn font(c: char) -> Option<[u8; 5]> {
+    Some(match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        _ => return None,
+    })
+}
+
+// x and y are in page pixels. returns the x just past the last character drawn
+fn draw_text(bitmap: &mut Bitmap, text: &str, x: i32, y: i32, scale: i32, colour: Colour) -> i32 {
+    let mut x = x;
+
+    for c in text.chars() {
+        if let Some(rows) = font(c) {
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                        bitmap.fill_rect(
+                            (x + col) * scale,
+                            (y + row as i32) * scale,
+                            scale,
+                            scale,
+                            colour,
+                        );
+                    }
+                }
+            }
+        }
+
+        x += GLYPH_WIDTH + 1;
+    }
+
+    x
+}
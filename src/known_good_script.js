@@ -0,0 +1,89 @@
+// NOT captured from the live page. this was rebuilt by hand from the rust port in src/draw.rs, so it only matches pages
+// drawn exactly the way draw.rs assumes (synth.rs makes its pages from it). until it's replaced, every real page is
+// likely to be reported as changed, which is why --check-script is off by default. to replace it, run `hts-prog6 fetch
+// --format script -o src/known_good_script.js` while logged in, which writes the page's own drawing script with a note
+// of where and when it was captured.
+
+var drawData = new Array(...);
+
+function drawPixel(l, t, w, h) {
+  document.write('<div style="position:absolute;left:' + l + 'px;top:' + t + 'px;width:' + w + 'px;height:' + h + 'px;background-color:#00FF00;"></div>');
+}
+
+function drawArc(x, y, r, s, e) {
+  var toRad = Math.PI / 180, xxLast = -1, yyLast = -1;
+  for (var ss = s; ss <= s + e; ss += 8) {
+    var xx = Math.round(x + r * Math.cos(ss * toRad));
+    var yy = Math.round(y - r * Math.sin(ss * toRad));
+    if (xx != xxLast || yy != yyLast) {
+      drawPixel(xx, yy, 1, 1);
+      xxLast = xx;
+      yyLast = yy;
+    }
+  }
+}
+
+function drawLine(x1, y1, x2, y2) {
+  if (x1 > x2) {
+    var t = x2; x2 = x1; x1 = t;
+    t = y2; y2 = y1; y1 = t;
+  }
+  var dx = x2 - x1, dy = Math.abs(y2 - y1), x = x1, y = y1;
+  var yInc = (y1 > y2) ? -1 : 1;
+  if (dx >= dy) {
+    var pr = dy << 1, pru = pr - (dx << 1), p = pr - dx, xOld = x;
+    while (dx > 0) {
+      x++;
+      if (p > 0) {
+        drawPixel(xOld, y, x - xOld, 1);
+        xOld = x;
+        y += yInc;
+        p += pru;
+      } else {
+        p += pr;
+      }
+      dx--;
+    }
+    drawPixel(xOld, y, x2 - xOld + 1, 1);
+  } else {
+    var pr = dx << 1, pru = pr - (dy << 1), p = pr - dy, yOld = y;
+    if (y2 <= y1) {
+      while (dy > 0) {
+        if (p > 0) {
+          drawPixel(x++, y, 1, yOld - y + 1);
+          yOld = y;
+          y += yInc;
+          p += pru;
+        } else {
+          y += yInc;
+          p += pr;
+        }
+        dy--;
+      }
+      drawPixel(x2, y2, 1, yOld - y2 + 1);
+    } else {
+      while (dy > 0) {
+        y += yInc;
+        if (p > 0) {
+          drawPixel(x++, yOld, 1, y - yOld);
+          yOld = y;
+          p += pru;
+        } else {
+          p += pr;
+        }
+        dy--;
+      }
+      drawPixel(x2, yOld, 1, y2 - yOld + 1);
+    }
+  }
+}
+
+for (var i = 0; i < drawData.length;) {
+  if (drawData[i + 2] >= 10) {
+    drawLine(drawData[i], drawData[i + 1], drawData[i + 2], drawData[i + 3]);
+    i += 4;
+  } else {
+    drawArc(drawData[i], drawData[i + 1], drawData[i + 2], drawData[i + 3], drawData[i + 4]);
+    i += 5;
+  }
+}
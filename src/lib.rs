@@ -0,0 +1,390 @@
+// the decoder for hackthissite programming mission 6: pull drawData out of the page, draw it the way the page's js
+// does, split the drawing into characters, put them in reading order and read them.
+// the binary in main.rs is a thin command line around this
+
+use std::{collections::HashSet, error::Error, fmt};
+
+use tracing::{debug, debug_span};
+
+pub mod analyze;
+pub mod ascii;
+pub mod bitmap;
+pub mod draw;
+#[cfg(feature = "net")]
+pub mod fetch;
+pub mod fingerprint;
+pub mod frame;
+pub mod glyph;
+pub mod html;
+pub mod image;
+pub mod model;
+pub mod moments;
+pub mod orientation;
+mod page;
+#[cfg(feature = "js-reference")]
+pub mod reference;
+pub mod report;
+pub mod segment;
+pub mod skeleton;
+pub mod spiral;
+pub mod svg;
+pub mod synth;
+pub mod timing;
+pub mod transform;
+pub mod vectorise;
+
+pub use analyze::{CharacterID, Recognition, RecognitionError, Strategy};
+pub use bitmap::GlyphBitmap;
+pub use draw::{
+    parse_draw_data, rasterize, DrawCommand, ExtractionError, RasterizationError, Rect,
+};
+#[cfg(feature = "net")]
+pub use fetch::NetError;
+pub use frame::Frame;
+pub use glyph::Glyph;
+pub use report::DecodeReport;
+pub use segment::{order, segment, OrderingError, Section, SegmentationError};
+pub use skeleton::StrokeGraph;
+pub use spiral::{Spiral, SpiralLayout};
+pub use transform::{Affine, Shape};
+
+use crate::timing::{DeadlineExceeded, StageTiming, Stopwatch};
+
+// every way the pipeline can fail, one variant per stage. each stage's own error says where in its input it gave up
+#[derive(Debug)]
+pub enum HTSError {
+    #[cfg(feature = "net")]
+    Fetch(NetError),
+    Extraction(ExtractionError),
+    #[cfg(feature = "js-reference")]
+    Reference(reference::ReferenceError),
+    Rasterization(RasterizationError),
+    Segmentation(SegmentationError),
+    Ordering(OrderingError),
+    Recognition(RecognitionError),
+    #[cfg(feature = "net")]
+    Submission(NetError),
+    Deadline(DeadlineExceeded),
+}
+
+impl fmt::Display for HTSError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(feature = "net")]
+            HTSError::Fetch(e) => write!(f, "fetching the page: {}", e),
+            HTSError::Extraction(e) => write!(f, "reading drawData: {}", e),
+            #[cfg(feature = "js-reference")]
+            HTSError::Reference(e) => write!(f, "running the page's js: {}", e),
+            HTSError::Rasterization(e) => write!(f, "drawing: {}", e),
+            HTSError::Segmentation(e) => write!(f, "splitting up characters: {}", e),
+            HTSError::Ordering(e) => write!(f, "ordering characters: {}", e),
+            HTSError::Recognition(e) => write!(f, "reading characters: {}", e),
+            #[cfg(feature = "net")]
+            HTSError::Submission(e) => write!(f, "submitting the answer: {}", e),
+            HTSError::Deadline(e) => write!(f, "out of time: {}", e),
+        }
+    }
+}
+
+impl Error for HTSError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            #[cfg(feature = "net")]
+            HTSError::Fetch(e) => Some(e),
+            HTSError::Extraction(e) => Some(e),
+            #[cfg(feature = "js-reference")]
+            HTSError::Reference(e) => Some(e),
+            HTSError::Rasterization(e) => Some(e),
+            HTSError::Segmentation(e) => Some(e),
+            HTSError::Ordering(e) => Some(e),
+            HTSError::Recognition(e) => Some(e),
+            #[cfg(feature = "net")]
+            HTSError::Submission(e) => Some(e),
+            HTSError::Deadline(e) => Some(e),
+        }
+    }
+}
+
+impl From<ExtractionError> for HTSError {
+    fn from(e: ExtractionError) -> HTSError {
+        HTSError::Extraction(e)
+    }
+}
+
+#[cfg(feature = "js-reference")]
+impl From<reference::ReferenceError> for HTSError {
+    fn from(e: reference::ReferenceError) -> HTSError {
+        HTSError::Reference(e)
+    }
+}
+
+impl From<RasterizationError> for HTSError {
+    fn from(e: RasterizationError) -> HTSError {
+        HTSError::Rasterization(e)
+    }
+}
+
+impl From<SegmentationError> for HTSError {
+    fn from(e: SegmentationError) -> HTSError {
+        HTSError::Segmentation(e)
+    }
+}
+
+impl From<OrderingError> for HTSError {
+    fn from(e: OrderingError) -> HTSError {
+        HTSError::Ordering(e)
+    }
+}
+
+impl From<RecognitionError> for HTSError {
+    fn from(e: RecognitionError) -> HTSError {
+        HTSError::Recognition(e)
+    }
+}
+
+impl From<DeadlineExceeded> for HTSError {
+    fn from(e: DeadlineExceeded) -> HTSError {
+        HTSError::Deadline(e)
+    }
+}
+
+// everything the pipeline worked out about one page
+#[derive(Debug)]
+pub struct Decoded {
+    pub commands: Vec<DrawCommand>,
+    pub curves: Vec<Rect>,
+    pub lines: Vec<Rect>,
+    // in reading order, read
+    pub glyphs: Vec<Glyph>,
+    // None when no spiral could be fitted through the characters
+    pub layout: Option<SpiralLayout>,
+    pub timings: Vec<StageTiming>,
+}
+
+impl Decoded {
+    pub fn answer(&self) -> String {
+        glyph::answer(&self.glyphs)
+    }
+
+    pub fn report(&self) -> DecodeReport {
+        DecodeReport::new(&self.glyphs, self.layout.as_ref(), self.timings.clone())
+    }
+
+    // every rect the page drew, arcs first
+    pub fn all_rects(&self) -> Vec<Rect> {
+        [self.curves.clone(), self.lines.clone()].concat()
+    }
+}
+
+// a saved page has the numbers inside "new Array(...)", a drawData list is just the numbers
+pub fn is_page(text: &str) -> bool {
+    text.contains("Array")
+}
+
+// reads every character, and keeps what it decided in the glyph. curves is every arc pixel on the page, the rules use
+// it to tell arcs and lines apart. what the rules measured isn't kept: it's slow to work out and only inspect wants
+// it, so inspect measures it again
+pub fn recognise(
+    glyphs: &mut [Glyph],
+    curves: &[Rect],
+    strategy: &Strategy,
+) -> Result<(), RecognitionError> {
+    let arcs: HashSet<(i32, i32)> = curves.iter().map(|i| (i.0, i.1)).collect();
+
+    for glyph in glyphs.iter_mut() {
+        recognise_one(glyph, &arcs, strategy)?;
+    }
+
+    Ok(())
+}
+
+// recognise, giving up as soon as clock runs out rather than reading every glyph first
+pub fn recognise_within(
+    glyphs: &mut [Glyph],
+    curves: &[Rect],
+    strategy: &Strategy,
+    clock: &Stopwatch,
+) -> Result<(), HTSError> {
+    let arcs: HashSet<(i32, i32)> = curves.iter().map(|i| (i.0, i.1)).collect();
+
+    for glyph in glyphs.iter_mut() {
+        clock.check("recognise")?;
+        recognise_one(glyph, &arcs, strategy)?;
+    }
+
+    Ok(())
+}
+
+fn recognise_one(
+    glyph: &mut Glyph,
+    arcs: &HashSet<(i32, i32)>,
+    strategy: &Strategy,
+) -> Result<(), RecognitionError> {
+    let _glyph =
+        debug_span!("glyph", index = glyph.index, angle = glyph.angle, section = ?glyph.section)
+            .entered();
+
+    let recognition = glyph.recognise(arcs, strategy)?;
+    debug!(
+        read_as = %recognition.character.val,
+        confidence = recognition.confidence,
+        steps = ?recognition.path.steps
+    );
+
+    glyph.recognition = Some(recognition);
+    Ok(())
+}
+
+// the page's divs, or what inspect prints, rather than the numbers that drew them. a saved page has "left:" in its js
+// too, so check is_page first
+pub fn is_divs(text: &str) -> bool {
+    text.contains("left:")
+}
+
+// the whole pipeline, from a saved page (or a bare drawData list, or the divs it drew) to the decoded characters
+pub fn decode_page(text: &str) -> Result<Decoded, HTSError> {
+    decode_page_within(text, &Strategy::default(), &mut Stopwatch::unlimited())
+}
+
+// decode_page, reading characters with strategy, with every stage timed on clock and the run abandoned as soon as a
+// stage ends past its deadline
+pub fn decode_page_within(
+    text: &str,
+    strategy: &Strategy,
+    clock: &mut Stopwatch,
+) -> Result<Decoded, HTSError> {
+    if !is_page(text) && is_divs(text) {
+        return decode_divs_within(text, strategy, clock);
+    }
+
+    let commands = clock.stage("parse", || {
+        let draw_data_array = if is_page(text) {
+            draw::scrape_draw_data(text)?
+        } else {
+            parse_draw_data(text)?
+        };
+        debug!(values = draw_data_array.len());
+
+        draw::commands(&draw_data_array)
+    })?;
+    debug!(commands = commands.len());
+
+    #[allow(unused_mut)]
+    let (mut curves, mut lines) = clock.stage("rasterize", || rasterize(&commands))?;
+    debug!(curves = curves.len(), lines = lines.len());
+
+    #[cfg(feature = "js-reference")]
+    if is_page(text) {
+        if let Some(redrawn) = clock.stage("reference", || check_against_page(text, &commands))? {
+            (curves, lines) = redrawn;
+        }
+    }
+
+    let mut glyphs = clock.stage("segment", || {
+        let mut glyphs = segment(&curves, &lines)?;
+        glyph::attach_commands(&mut glyphs, &commands);
+        Ok::<_, SegmentationError>(glyphs)
+    })?;
+    debug!(characters = glyphs.len());
+
+    let layout = clock.stage("order", || order(&mut glyphs))?;
+
+    clock.stage_checking("recognise", |clock| {
+        recognise_within(&mut glyphs, &curves, strategy, clock)
+    })?;
+
+    Ok(Decoded {
+        commands,
+        curves,
+        lines,
+        glyphs,
+        layout,
+        timings: clock.timings().to_vec(),
+    })
+}
+
+// divs don't say which of them are arcs, and there are no commands to ask. every glyph is vectorised instead, and its
+// arcs stand in for the page's
+fn decode_divs_within(
+    text: &str,
+    strategy: &Strategy,
+    clock: &mut Stopwatch,
+) -> Result<Decoded, HTSError> {
+    let rects = clock.stage("parse", || draw::parse_divs(text))?;
+    debug!(rects = rects.len());
+
+    let mut glyphs = clock.stage("segment", || segment(&[], &rects))?;
+    debug!(characters = glyphs.len());
+
+    let (commands, curves, lines) = clock.stage("vectorise", || {
+        let (mut commands, mut curves, mut lines) = (Vec::new(), Vec::new(), Vec::new());
+
+        for glyph in glyphs.iter_mut() {
+            glyph.commands = vectorise::vectorise(&glyph.rects);
+            debug!(
+                index = glyph.index,
+                commands = glyph.commands.len(),
+                "vectorised"
+            );
+
+            let (mut arcs, mut rest) = vectorise::split_arcs(&glyph.rects, &glyph.commands);
+            curves.append(&mut arcs);
+            lines.append(&mut rest);
+            commands.extend_from_slice(&glyph.commands);
+        }
+
+        Ok::<_, HTSError>((commands, curves, lines))
+    })?;
+
+    let layout = clock.stage("order", || order(&mut glyphs))?;
+
+    clock.stage_checking("recognise", |clock| {
+        recognise_within(&mut glyphs, &curves, strategy, clock)
+    })?;
+
+    Ok(Decoded {
+        commands,
+        curves,
+        lines,
+        glyphs,
+        layout,
+        timings: clock.timings().to_vec(),
+    })
+}
+
+// (curves, lines)
+#[cfg(feature = "js-reference")]
+type Drawing = (Vec<Rect>, Vec<Rect>);
+
+// check our translations against the page's own js. if they disagree the page's output wins, and that's what comes back
+#[cfg(feature = "js-reference")]
+fn check_against_page(
+    text: &str,
+    commands: &[DrawCommand],
+) -> Result<Option<Drawing>, reference::ReferenceError> {
+    let mut js = match reference::Reference::from_page(text) {
+        Ok(js) => js,
+        Err(e) => {
+            tracing::warn!("skipping js reference check: {}", e);
+            return Ok(None);
+        }
+    };
+
+    let mismatches = js.cross_check(commands)?;
+
+    if mismatches.is_empty() {
+        return Ok(None);
+    }
+
+    for m in mismatches.iter() {
+        tracing::warn!(
+            index = m.index,
+            command = ?m.command,
+            native = ?m.native,
+            page = ?m.reference,
+            "command drawn differently by the page"
+        );
+    }
+
+    js.rasterize(commands).map(Some)
+}
@@ -1,634 +1,497 @@
-use std::{collections::HashSet, error::Error, f64::consts::PI, fmt, fs::File, io::Write};
+use std::{
+    collections::HashSet,
+    error::Error,
+    fs,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use clap::Parser;
+use tracing::warn;
+use tracing_subscriber::EnvFilter;
+
+use hts_prog6::{
+    ascii, decode_page_within, draw, fetch, fingerprint, html, image, is_page,
+    model::{self, Example, Model},
+    skeleton::NodeKind,
+    svg,
+    timing::Stopwatch,
+    Decoded, HTSError, NetError, Rect, Strategy,
+};
+
+use crate::cli::{
+    Cli, Command, DecodeFormat, FetchFormat, InspectFormat, Recogniser, RenderArgs, RenderFormat,
+    Source, SubmitFormat, TrainArgs,
+};
+
+mod cli;
 
-use reqwest::header::{HeaderMap, COOKIE};
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
 
-use crate::analyze::Analyze;
+    if let Err(e) = init_logging(&cli.log) {
+        eprintln!("error: bad --log filter {:?}: {}", cli.log, e);
+        std::process::exit(2);
+    }
 
-pub mod analyze;
+    let mut clock = Stopwatch::new(cli.deadline.map(Duration::from_millis));
+    let result = run(cli.command, &mut clock).await;
 
-#[derive(Debug)]
-pub enum HTSError {
-    CouldNotFindNext,
-    CharacterUnrecognizable,
-}
+    if cli.timings {
+        eprint!("{}", clock.summary());
+    }
 
-impl fmt::Display for HTSError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            HTSError::CouldNotFindNext => write!(f, "unable to find next coordinate"),
-            HTSError::CharacterUnrecognizable => write!(f, "unable to identify character"),
-        }
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
     }
 }
 
-const COOKIES: &str = "HackThisSite=5eku78f0d06s302455vep962d1";
-
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    let client = reqwest::Client::new();
-
-    let url = "https://www.hackthissite.org/missions/prog/6/image/";
-
-    let mut headers = HeaderMap::new();
-
-    // insert cookies manually
-    headers.insert(COOKIE, COOKIES.parse().unwrap());
-
-    let res_body = client
-        .get(url)
-        .headers(headers)
-        .send()
-        .await?
-        .text()
-        .await?;
-
-    // create a file containing the scraped html & js for debugging purposes
-    let mut html = File::create("read_me.html").unwrap();
-    html.write_all(res_body.as_bytes()).unwrap();
-
-    // get ArrayData
-
-    if let Some(index) = res_body.find("Array") {
-        // scrape the source code first
-        // {index} is 192. index 198 is where the first number of the array can be found
-        let start_index = index + 6;
-        eprintln!("found the first number at position {}", start_index);
-
-        if let Some(end_index_plus_one) = res_body.find(");") {
-            eprintln!("found the last number at position {}", end_index_plus_one);
-            let end_index_plus_one = end_index_plus_one;
-
-            {
-                let draw_data_string: String = res_body[start_index..end_index_plus_one].to_owned();
-
-                // get drawData array. collect all the values into a vector
-                let draw_data_vec: Vec<i32> = draw_data_string
-                    .split(',')
-                    .map(|num_str| num_str.parse::<i32>().unwrap())
-                    .collect();
-
-                let draw_data_array = draw_data_vec.into_boxed_slice();
-
-                // println!("{:?}", draw_data_array); // debugging
-
-                // curve refers to arc
-                // the tuple has a format of (left, top, width, height) where (left, top) are positions in the xy plane
-                let mut curves: Vec<(i32, i32, i32, i32)> = Vec::new();
-
-                // Unlike the arcs, the lines don't have a constant width and height of one.
-                let mut lines: Vec<(i32, i32, i32, i32)> = Vec::new();
-
-                let mut i = 0;
-
-                // translate the javascript from the source code into rust in order to grab all the x and y coordinates of the green-fill div containers
-                while i < draw_data_array.len() {
-                    if draw_data_array[i + 2] >= 10 {
-                        let mut line_deets = get_line_coordinates(
-                            draw_data_array[i],
-                            draw_data_array[i + 1],
-                            draw_data_array[i + 2],
-                            draw_data_array[i + 3],
-                        )
-                        .unwrap();
-
-                        i += 4;
-
-                        lines.append(&mut line_deets);
-                    } else {
-                        let mut curve_deets = get_curve_coordinates(
-                            draw_data_array[i],
-                            draw_data_array[i + 1],
-                            draw_data_array[i + 2],
-                            draw_data_array[i + 3],
-                            draw_data_array[i + 4],
-                        )
-                        .unwrap();
-
-                        i += 5;
-
-                        curves.append(&mut curve_deets);
-                    }
-                }
-
-                // debugging
-                // println!("{:?}", curves);
-                // println!("line coordinates: {:?}", lines);
-
-                let mut all_coordinates = curves.clone();
-                all_coordinates.append(&mut lines);
-
-                let all_y_values: Vec<i32> = all_coordinates.iter().map(|i| i.1).collect();
-                let min_y = all_y_values.clone().into_iter().min().unwrap(); // the 'top' value for the top-most character
-
-                // get one of the coordinates from the first top-most character
-
-                let mut max_coordinates: Vec<(i32, i32, i32, i32)> = Vec::new();
+async fn run(command: Command, clock: &mut Stopwatch) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Fetch(args) => {
+            let page = network(
+                clock,
+                "fetch",
+                HTSError::Fetch,
+                fetch::fetch_page(&args.session),
+            )
+            .await?;
+            if args.check_script {
+                check_page(&page, clock);
+            }
 
-                for i in all_coordinates.iter() {
-                    if i.1 == min_y {
-                        let coordinates_max = (i.0, i.1, i.2, i.3);
-                        max_coordinates.push(coordinates_max);
-                        break;
-                    }
+            let contents = match args.format {
+                FetchFormat::Html => page,
+                FetchFormat::DrawData => {
+                    let draw_data = draw::scrape_draw_data(&page).map_err(HTSError::Extraction)?;
+                    draw_data
+                        .iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<String>>()
+                        .join(",")
                 }
+                FetchFormat::Script => format!(
+                    "// captured from {} on {}\n\n{}",
+                    fetch::PAGE_URL,
+                    today(),
+                    fingerprint::drawing_script(&page)
+                ),
+            };
+
+            fs::write(&args.output, contents)?;
+        }
 
-                let first_char = analyze_character(all_coordinates.clone(), max_coordinates);
-
-                // debug
-                // for i in first_char.iter() {
-                //     println!(
-                //         "left:{}px;top:{}px;width:{}px;height:{}px;",
-                //         i.0, i.1, i.2, i.3
-                //     );
-                // }
-
-                // (vector of coordinates, index, angle, section)
-                let mut known_character_coordinates: Vec<(
-                    Vec<(i32, i32, i32, i32)>,
-                    i32,
-                    i32,
-                    Section,
-                )> = Vec::new();
-
-                let mut angle = 0;
-                known_character_coordinates.push((first_char.clone(), 0, angle, Section::A));
-
-                let mut prev_coord = first_char;
-                let sections = [Section::A, Section::B, Section::C, Section::D];
-
-                let mut sec_index = 0;
-
-                // debug purposes
-                let mut stop_at_nine = 0;
-
-                // fix performance issue
-                let mut all_coord_except_known = all_coordinates.clone();
-                all_coord_except_known.retain(|x| !prev_coord.contains(x));
-
-                // read in circular. after every ninth iteration, the section changes. there are 253 characters. looping 252 times because the first char is already provided
-                for i in 1..253 {
-                    let section = &sections[sec_index];
-
-                    // at every tenth character, move to the next section
-                    if i % 9 == 0 {
-                        if sec_index != 3 {
-                            sec_index += 1;
-                            // eprintln!("entering sec {} at loop {}", sec_index, i + 1);
-                        } else {
-                            sec_index = 0;
-                        }
+        Command::Decode(args) => {
+            let decoded = decode(&args.source, clock).await?;
+
+            let text = match args.format {
+                DecodeFormat::Text => decoded.answer(),
+                DecodeFormat::Verbose => {
+                    let mut text = String::new();
+
+                    for glyph in decoded.glyphs.iter() {
+                        text.push_str(&format!(
+                            "{:>3} angle {:>3} section {:?} rects {:>3} -> {}\n",
+                            glyph.index,
+                            glyph.angle,
+                            glyph.section,
+                            glyph.rects.len(),
+                            glyph.value().unwrap_or("?")
+                        ));
                     }
 
-                    let next_coord = section
-                        .get_next(prev_coord, all_coord_except_known.clone())
-                        .unwrap();
-
-                    let mut nxt_coord_vec = Vec::new();
-                    nxt_coord_vec.push(next_coord.clone());
+                    text.push_str(&decoded.answer());
+                    text
+                }
+                DecodeFormat::Json => serde_json::to_string_pretty(&decoded.report())? + "\n",
+            };
 
-                    prev_coord = nxt_coord_vec;
+            write_output(&args.output, &text)?;
+        }
 
-                    let mut initial_coordinates = Vec::new();
-                    initial_coordinates.push(next_coord);
+        Command::Render(args) => {
+            let decoded = decode(&args.source, clock).await?;
 
-                    let all_coord_of_next =
-                        analyze_character(all_coord_except_known.clone(), initial_coordinates);
+            match args.format {
+                RenderFormat::Css => {
+                    let mut text = String::new();
 
-                    if angle == 350 {
-                        angle = 0;
-                    } else {
-                        angle = angle + 10;
+                    for glyph in decoded.glyphs.iter() {
+                        text.push_str(&format!("index {}\n", glyph.index));
+                        text.push_str(&css(&glyph.rects));
                     }
 
-                    known_character_coordinates.push((
-                        all_coord_of_next.clone(),
-                        i,
-                        angle,
-                        *section,
-                    ));
-                    all_coord_except_known.retain(|x| !all_coord_of_next.contains(x));
-
-                    // debug
-
-                    println!("iteration {} over", i);
-                    stop_at_nine += 1;
-                    // if stop_at_nine == 253 {
-                    //     break;
-                    // }
+                    write_output(&args.output, &text)?;
                 }
+                RenderFormat::Png | RenderFormat::Ppm => render_images(&decoded, &args)?,
+                RenderFormat::Svg => write_output(
+                    &args.output,
+                    &svg::render(&decoded.commands, &decoded.glyphs),
+                )?,
+                RenderFormat::Html => {
+                    let report =
+                        html::report(&decoded.answer(), &decoded.all_rects(), &decoded.glyphs);
+                    write_output(&args.output, &report)?;
+                }
+            }
+        }
 
-                println!("last section read: {:?}", &sections[sec_index]);
-
-                // debug
+        Command::Submit(args) => {
+            let answer = match args.answer {
+                Some(answer) => answer,
+                None => decode(&args.source, clock).await?.answer(),
+            };
+
+            let response = network(
+                clock,
+                "submit",
+                HTSError::Submission,
+                fetch::submit(&args.source.session, &answer),
+            )
+            .await?;
+
+            if let Some(path) = &args.output {
+                fs::write(path, &response)?;
+            }
 
-                // (vector of coordinates, index, angle, section)
+            match args.format {
+                SubmitFormat::Status => println!("submitted {}", answer),
+                SubmitFormat::Html => println!("{}", response),
+            }
+        }
 
-                let test = Analyze {
-                    coordinates_vec: known_character_coordinates[11].0.clone(),
-                    coordinates_angle: known_character_coordinates[11].2,
-                    section: known_character_coordinates[11].3,
-                };
+        Command::Inspect(args) => {
+            let decoded = decode(&args.source, clock).await?;
 
-                // test.identify_char(curves);
+            if args.index >= decoded.glyphs.len() {
+                return Err(format!("there are only {} characters", decoded.glyphs.len()).into());
+            }
 
-                println!("char identified = {:?}", test.identify_char(curves));
+            let text = match args.format {
+                InspectFormat::Text => inspect(&decoded, args.index),
+            };
 
-                // debug
-                println!("index 108");
-                println!("angle: {:?}", known_character_coordinates[198].2);
-                for i in known_character_coordinates[198].0.iter() {
-                    println!(
-                        "left:{}px;top:{}px;width:{}px;height:{}px;",
-                        i.0, i.1, i.2, i.3
-                    );
-                }
+            write_output(&args.output, &text)?;
+        }
 
-                // println!("no. of curves: {:?}", curves.len());
-
-                // println!("index 132");
-                // for i in known_character_coordinates[132].iter() {
-                //     println!(
-                //         "left:{}px;top:{}px;width:{}px;height:{}px;",
-                //         i.0, i.1, i.2, i.3
-                //     );
-                // }
-
-                println!("index 252");
-                println!("angle: {:?}", known_character_coordinates[252].2);
-                for i in known_character_coordinates[252].0.iter() {
-                    println!(
-                        "left:{}px;top:{}px;width:{}px;height:{}px;",
-                        i.0, i.1, i.2, i.3
-                    );
-                }
-            }
+        Command::Train(args) => {
+            print!("{}", train(&args)?);
         }
     }
 
     Ok(())
 }
 
-// separate the circle into four quarters. ABCD, and go anti-clockwise, starting from the top.
-#[derive(PartialEq, Debug, Clone, Copy)]
-pub enum Section {
-    A,
-    B,
-    C,
-    D,
-}
-
-impl Section {
-    fn get_next(
-        &self,
-        prev: Vec<(i32, i32, i32, i32)>,
-        all_coordinates: Vec<(i32, i32, i32, i32)>,
-    ) -> Result<(i32, i32, i32, i32), HTSError> {
-        // get random coordinate from the previous character
-        let cc = prev[0];
-        let (cc_x, cc_y) = (cc.0, cc.1);
-
-        // debug
-        // println!("prev: {:?}", prev);
-
-        let mut next_coordinates: Vec<(i32, i32, i32, i32)> = Vec::new();
-
-        match self {
-            Section::A => {
-                let mut smallest_y = 50000;
-
-                for i in all_coordinates.iter() {
-                    let (x, y, w, h) = (i.0, i.1, i.2, i.3);
-
-                    if x <= cc_x && y >= cc_y {
-                        if y <= smallest_y {
-                            smallest_y = y;
-                            next_coordinates.clear();
-                            next_coordinates.push((x, y, w, h));
-                        }
-                    }
-                }
-            }
-            Section::B => {
-                let mut smallest_x = 50000;
-
-                for i in all_coordinates.iter() {
-                    let (x, y, w, h) = (i.0, i.1, i.2, i.3);
-
-                    if x >= cc_x && y >= cc_y {
-                        if x <= smallest_x {
-                            smallest_x = x;
-                            next_coordinates.clear();
-                            next_coordinates.push((x, y, w, h));
-                        }
-                    }
-                }
-            }
-            Section::C => {
-                let mut largest_y = -50000;
-
-                for i in all_coordinates.iter() {
-                    let (x, y, w, h) = (i.0, i.1, i.2, i.3);
-
-                    if x >= cc_x && y <= cc_y {
-                        if y >= largest_y {
-                            largest_y = y;
-                            next_coordinates.clear();
-                            next_coordinates.push((x, y, w, h));
-                        }
-                    }
-                }
-            }
-            Section::D => {
-                let mut largest_x = -50000;
-
-                for i in all_coordinates.iter() {
-                    let (x, y, w, h) = (i.0, i.1, i.2, i.3);
-
-                    if x <= cc_x && y <= cc_y {
-                        if x >= largest_x {
-                            largest_x = x;
-                            next_coordinates.clear();
-                            next_coordinates.push((x, y, w, h));
-                        }
-                    }
-                }
-            }
-        }
+// logs go to stderr so they never end up mixed into an answer or a render written to stdout
+fn init_logging(filter: &str) -> Result<(), Box<dyn Error>> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_new(filter)?)
+        .with_writer(std::io::stderr)
+        .init();
 
-        let next_coordinate: Option<(i32, i32, i32, i32)> = next_coordinates.first().copied();
+    Ok(())
+}
 
-        if let Some(coord) = next_coordinate {
-            return Ok(coord);
-        } else {
-            return Err(HTSError::CouldNotFindNext);
+// runs a request against what's left of the deadline. a slow server gets cut off instead of eating into the time the
+// answer needs to get back
+async fn network<T>(
+    clock: &mut Stopwatch,
+    stage: &'static str,
+    wrap: fn(NetError) -> HTSError,
+    request: impl Future<Output = Result<T, NetError>>,
+) -> Result<T, HTSError> {
+    let start = Instant::now();
+
+    let result = match clock.remaining() {
+        Some(remaining) => tokio::time::timeout(remaining, request).await.ok(),
+        None => Some(request.await),
+    };
+
+    clock.record(stage, start.elapsed());
+
+    match result {
+        Some(result) => {
+            let value = result.map_err(wrap)?;
+            clock.check(stage)?;
+            Ok(value)
         }
+        None => Err(clock.overran(stage).into()),
     }
 }
 
-fn analyze_character(
-    all_coordinates: Vec<(i32, i32, i32, i32)>,
-    initial_coordinates: Vec<(i32, i32, i32, i32)>,
-) -> Vec<(i32, i32, i32, i32)> {
-    let mut known_character_coordinates: Vec<(i32, i32, i32, i32)> = Vec::new();
+// reads the input file, or downloads a fresh page when there isn't one
+async fn load(source: &Source, clock: &mut Stopwatch) -> Result<String, Box<dyn Error>> {
+    let text = match &source.input {
+        Some(path) => {
+            fs::read_to_string(path).map_err(|e| format!("reading {}: {}", path.display(), e))?
+        }
+        None => {
+            let page = network(
+                clock,
+                "fetch",
+                HTSError::Fetch,
+                fetch::fetch_page(&source.session),
+            )
+            .await?;
+
+            // create a file containing the scraped html & js for debugging purposes
+            fs::write("read_me.html", &page)?;
+
+            page
+        }
+    };
 
-    for i in initial_coordinates {
-        known_character_coordinates.push((i.0, i.1, i.2, i.3));
+    if source.check_script && is_page(&text) {
+        check_page(&text, clock);
     }
 
-    // preventing iteration through stuff that's already been iterated through.
-    let mut already_iterated_through: HashSet<(i32, i32, i32, i32)> = HashSet::new();
-
-    // annotating this part in detail because i myself am not sure what i did here
-    'main_loop: loop {
-        let initial_len_of_known = known_character_coordinates.len(); // initially one, at the first loop
-
-        // the elements in this will get added to known_character_coordinates at the end of each loop
-        let mut new_coordinates: HashSet<(i32, i32, i32, i32)> = HashSet::new();
-
-        // declare loop "outer" to loop through the known_character_coordinates vector, which gets updated each time the main loop resets
-        'outer: for cc in known_character_coordinates.iter() {
-            // declare the cc variables. cc = character coordinate (that we have already confirmed to belong to the character we are analysing)
-            let (cc_x, cc_y, cc_w, cc_h) = (cc.0, cc.1, cc.2, cc.3);
-
-            // assuming this is not our first "main" loop, check if we've already iterated through cc. if we have, then move to the next cc iteration.
-            if already_iterated_through.contains(&(cc_x, cc_y, cc_w, cc_h)) {
-                continue 'outer;
-            }
-
-            // this one loops through each coordinate that we have collected from the algorithms provided by the source code (drawArc, drawLine)
-            'inner: for i in all_coordinates.iter() {
-                let x = i.0;
-                let y = i.1;
-                let w = i.2;
-                let h = i.3;
-
-                // finding adjacent coordinates. basically a bunch of condtions are defined below to find coordinates that are "connected" with each other. as of 7/14/23 this section is kind of a mess and some of the defined conditions may be accidental duplicates. ill clean it up later.
-
-                // if one of the conditions return true, we insert the i value into new_coordinates and move on to the next iteration of "inner" loop. this way we can test a single element of known_coordinates against all of the conditions
-                if x == cc_x && y == cc_y && (w != cc_w || h != cc_h) {
-                    new_coordinates.insert((x, y, w, h));
-
-                    continue 'inner;
-                }
-
-                // 30 deg E & 40 deg 4. idek visualizing tetris as i make these up.
-                if (x == cc_w + cc_x && y == cc_y - h)
-                    || (x == cc_x - w && y == cc_y - cc_h)
-                    || (x == cc_x - w && y == cc_y - h)
-                {
-                    new_coordinates.insert((x, y, w, h));
-
-                    continue 'inner;
-                }
-
-                // ^
-                if (cc_x == x + 1 && cc_y == y - 1) || (cc_y == y - 1 && x == cc_x + 1) {
-                    new_coordinates.insert((x, y, w, h));
-
-                    continue 'inner;
-                }
+    Ok(text)
+}
 
-                // upside down ^
-                if (x + 1 == cc_x && y + 1 == cc_y) || (cc_x + 1 == x && y + 1 == cc_y) {
-                    new_coordinates.insert((x, y, w, h));
+// loads the source and decodes it with the recogniser it asks for
+async fn decode(source: &Source, clock: &mut Stopwatch) -> Result<Decoded, Box<dyn Error>> {
+    let strategy = match source.strategy {
+        Recogniser::Rules => Strategy::Rules,
+        Recogniser::Moments => Strategy::Moments,
+        Recogniser::Learned => Strategy::Learned(Arc::new(Model::load(&source.model)?)),
+    };
 
-                    continue 'inner;
-                }
+    let text = load(source, clock).await?;
+    Ok(decode_page_within(&text, &strategy, clock)?)
+}
 
-                if ((x == cc_x - 1 || x == cc_x + 1) && w == cc_w && h == cc_h && y == cc_y)
-                    || ((y == cc_y - 1 || y == cc_y + 1) && w == cc_w && h == cc_h && x == cc_x)
-                {
-                    new_coordinates.insert((x, y, w, h));
+// trains on synthetic pages and any corrected real ones, and says how the model did on what it didn't see
+fn train(args: &TrainArgs) -> Result<String, Box<dyn Error>> {
+    let mut examples: Vec<Example> = model::synthetic_examples_from(0..args.synthetic)?;
 
-                    continue 'inner;
-                }
+    for corrected in args.corrected.iter() {
+        let Some((page, answer)) = corrected.split_once('=') else {
+            return Err(format!("{:?} isn't PAGE=ANSWER", corrected).into());
+        };
+        let read =
+            |path: &str| fs::read_to_string(path).map_err(|e| format!("reading {}: {}", path, e));
 
-                // "A"
-                if (cc_x <= w + x && cc_x >= x) && (y <= cc_y + cc_h && y >= cc_y) {
-                    new_coordinates.insert((x, y, w, h));
+        examples.append(&mut model::page_examples(&read(page)?, &read(answer)?)?);
+    }
 
-                    continue 'inner;
-                }
+    let (training, held_out) = model::split(examples, args.holdout);
+    let model = Model::train(&training);
+    let evaluation = model.evaluate(&held_out);
+    model.save(&args.output)?;
+
+    let mut text = format!(
+        "trained on {} characters, {} of {} held out read right ({:.1}%)\n",
+        training.len(),
+        evaluation.right,
+        evaluation.total,
+        evaluation.accuracy() * 100.0
+    );
 
-                if (y >= cc_y && y <= cc_y + cc_h) && (x >= cc_x && x <= cc_x + cc_w) {
-                    new_coordinates.insert((x, y, w, h));
+    let mut confusions: Vec<_> = evaluation.confusions.iter().collect();
+    confusions.sort_by_key(|i| std::cmp::Reverse(*i.1));
+    for ((was, read), n) in confusions {
+        text.push_str(&format!("  {} read as {}: {}\n", was, read, n));
+    }
 
-                    continue 'inner;
-                }
+    Ok(text)
+}
 
-                if (x <= cc_x + cc_w && x >= cc_x - w) && (y >= cc_y - h && y <= cc_y - h + cc_h) {
-                    new_coordinates.insert((x, y, w, h));
+// yyyy-mm-dd in utc, from the days since 1970 (howard hinnant's civil_from_days)
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |i| i.as_secs() / 86400) as i64;
+
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
 
-                    continue 'inner;
-                }
+// make sure the page still draws things the way our translations expect before trusting anything we decode from it.
+// only done when asked for with --check-script, the known good copy isn't from the real page yet
+fn check_page(page: &str, clock: &mut Stopwatch) {
+    let start = Instant::now();
 
-                // once you know how to analyze all fifteen upright characters from the top, chances are you'll be able to easily analyze the other characters as well regardless of position.
-            }
+    if let Err(changed) = fingerprint::check(page) {
+        warn!("{}", changed);
+    }
 
-            // after we have tested the known-coordinate against all the coordinates, we insert it into already_iterated_through. if this is our first iteration of 'main' loop then we only have one element in known_coordinates so there's only one 'outer' iteration.
-            already_iterated_through.insert((cc_x, cc_y, cc_w, cc_h));
-        }
+    clock.record("fingerprint", start.elapsed());
+}
 
-        // after known_coordinates has finished iterating through itself, update the vector with the new_coordinates you have collected based on the conditions we ran the known_coordinate through above.
-        known_character_coordinates.extend(new_coordinates.into_iter());
-
-        // get the new length of known_coordinates. unless there's something seriously wrong, this variable will never contain one. this is because new_coordinates always gets updated in each loop.
-        let new_len_of_known = known_character_coordinates.len();
-
-        // let already_iterated_through_len = already_iterated_through.len();
-
-        // debug
-        // println!(
-        //     "already iterated through len: {:?}",
-        //     already_iterated_through_len
-        // );
-        // println!("new len of known: {:?}", new_len_of_known);
-        // println!("already iterated through {:?}", already_iterated_through);
-        // println!(
-        //     "known_character_coordinates: {:?}",
-        //     known_character_coordinates
-        // );
-
-        if new_len_of_known == initial_len_of_known {
-            // println!("breaking main loop now...");
-            break 'main_loop;
+// writes canvas.png (or .ppm) plus glyph_NNN images for every character if asked for
+fn render_images(decoded: &Decoded, args: &RenderArgs) -> Result<(), Box<dyn Error>> {
+    let dir = args
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("render"));
+    fs::create_dir_all(&dir)?;
+
+    let options = image::RenderOptions {
+        scale: args.scale,
+        colour_segments: args.colour,
+        index_labels: args.index_labels,
+        char_labels: args.char_labels,
+    };
+
+    let all_rects = decoded.all_rects();
+
+    let save = |bitmap: image::Bitmap, name: &str| -> Result<(), Box<dyn Error>> {
+        let (bytes, extension) = match args.format {
+            RenderFormat::Ppm => (bitmap.to_ppm(), "ppm"),
+            _ => (bitmap.to_png()?, "png"),
+        };
+
+        fs::write(
+            Path::new(&dir).join(format!("{}.{}", name, extension)),
+            bytes,
+        )?;
+        Ok(())
+    };
+
+    save(
+        image::render_canvas(&all_rects, &decoded.glyphs, options)?,
+        "canvas",
+    )?;
+
+    if args.glyphs {
+        for segment in decoded.glyphs.iter() {
+            save(
+                image::render_segment(segment, options)?,
+                &format!("glyph_{:03}", segment.index),
+            )?;
         }
     }
 
-    /*  this variable represents the number of coordinates we have collected so far.
-    this is confusing because due to the variable name, you would expect this role to be carried by known_character_coordinates.
-    While known_character_coordinates does contain all the coordinates we have confirmed to belong to a character we are analysing,
-    the difference between already_iterated_through and known_character_coordinates is that the latter contains several duplicates
-    of the same coordinate whereas the former does not.    */
-    let collected_coordinates: Vec<(i32, i32, i32, i32)> =
-        already_iterated_through.into_iter().collect();
-
-    return collected_coordinates;
+    Ok(())
 }
 
-// ---------------------translated the functions from the js in the source code into rust-----------------
+// everything about one character: where it is, what the rules measured, how they decided, and what it looks like
+fn inspect(decoded: &Decoded, index: usize) -> String {
+    let glyph = &decoded.glyphs[index];
 
-type CurveCoordinatesAndDeets = Result<Vec<(i32, i32, i32, i32)>, Box<dyn Error>>;
+    let arcs: HashSet<(i32, i32)> = decoded.curves.iter().map(|i| (i.0, i.1)).collect();
+    let features = glyph.measure(&arcs);
 
-// drawArc func
-fn get_curve_coordinates(x: i32, y: i32, r: i32, s: i32, e: i32) -> CurveCoordinatesAndDeets {
-    // convert to f64 in order to perform the trig calculations.
-    let (x, y, r, s, e) = (
-        f64::from(x),
-        f64::from(y),
-        f64::from(r),
-        f64::from(s),
-        f64::from(e),
+    let mut text = format!(
+        "index {}\nangle: {}\nsection: {:?}\n",
+        glyph.index, glyph.angle, glyph.section
     );
 
-    let mut curve_deets: Vec<(i32, i32, i32, i32)> = Vec::new();
-
-    let to_radian: f64 = PI / 180.0;
-    let mut xx_last: f64 = -1.0;
-    let mut yy_last: f64 = -1.0;
-    let mut ss = s;
+    if let Some(placement) = glyph.placement {
+        text.push_str(&format!(
+            "spiral: turn {} along {:.1} off by {:.1}\n",
+            placement.turn, placement.along, placement.residual
+        ));
+    }
 
-    while ss <= s + e {
-        // let ss_to_rad = ss * to_radian;
-        let xx = (x + r * (ss * to_radian).cos()).round();
-        let yy = (y - r * (ss * to_radian).sin()).round();
+    let (left, top, width, height) = features.bounding_box;
+    text.push_str(&format!(
+        "bounding box: left {} top {} width {} height {}\n",
+        left, top, width, height
+    ));
+
+    text.push_str(&format!(
+        "features: {} rects, {} arc pixels, {} line rects\n",
+        features.rects, features.arc_pixels, features.line_rects
+    ));
+    text.push_str(&format!("  highest x: {:?}\n", features.highest_x_coords));
+    text.push_str(&format!("  lowest x: {:?}\n", features.lowest_x_coords));
+    text.push_str(&format!("  highest y: {:?}\n", features.highest_y_coords));
+    text.push_str(&format!("  lowest y: {:?}\n", features.lowest_y_coords));
+    text.push_str(&format!(
+        "normalised ({:016x}):\n",
+        features.bitmap.fingerprint()
+    ));
+    text.push_str(&features.bitmap.to_string());
+    text.push_str(&format!(
+        "skeleton: {} ends, {} junctions, {} loops\n",
+        features.strokes.count(NodeKind::End),
+        features.strokes.count(NodeKind::Junction),
+        features.strokes.count(NodeKind::Loop)
+    ));
+    text.push_str(&features.strokes.skeleton().to_string());
+    for stroke in features.strokes.edges.iter() {
+        text.push_str(&format!(
+            "  {} -> {}: {:.1} cells, curvature {:.2}, {:.0}% arc\n",
+            stroke.from,
+            stroke.to,
+            stroke.length,
+            stroke.curvature,
+            stroke.arc * 100.0
+        ));
+    }
+    if let Some(moments) = &features.moments {
+        let hu: Vec<String> = moments.hu.iter().map(|i| format!("{:.3}", i)).collect();
+        let radial: Vec<String> = moments.radial.iter().map(|i| format!("{:.2}", i)).collect();
+        text.push_str(&format!(
+            "moments: hu [{}], rings [{}], {:.0}% arc\n",
+            hu.join(", "),
+            radial.join(", "),
+            moments.arc_share * 100.0
+        ));
+    }
 
-        if xx != xx_last || yy != yy_last {
-            curve_deets.push((xx as i32, yy as i32, 1, 1));
-            xx_last = xx;
-            yy_last = yy;
+    if let Some(recognition) = &glyph.recognition {
+        text.push_str("decision path:\n");
+        for step in recognition.path.iter() {
+            text.push_str(&format!("  {}\n", step));
         }
-
-        ss += 8.0;
+        text.push_str(&format!(
+            "identified as: {} (confidence {:.2}, alternatives {:?})\n",
+            recognition.character.val, recognition.confidence, recognition.alternatives
+        ));
     }
 
-    return Ok(curve_deets);
-}
-
-type LineCoordinatesAndDeets = Result<Vec<(i32, i32, i32, i32)>, Box<dyn Error>>;
-
-// drawLine func
-fn get_line_coordinates(x1_i: i32, y1_i: i32, x2_i: i32, y2_i: i32) -> LineCoordinatesAndDeets {
-    let mut line_deets: Vec<(i32, i32, i32, i32)> = Vec::new();
-
-    let (mut x1, mut y1, mut x2, mut y2) = (x1_i, y1_i, x2_i, y2_i);
-
-    if x1 > x2 {
-        let x2_initial = x2;
-        let y2_initial = y2;
-
-        x2 = x1;
-        y2 = y1;
-        x1 = x2_initial;
-        y1 = y2_initial;
+    text.push_str(&format!("drawn by {} commands:\n", glyph.commands.len()));
+    for command in glyph.commands.iter() {
+        text.push_str(&format!("  {:?}\n", command));
     }
 
-    let mut dx = x2 - x1;
-    let mut dy = (y2 - y1).abs();
-    let mut x = x1;
-    let mut y = y1;
-    let y_inc = if y1 > y2 { -1 } else { 1 };
-
-    if dx >= dy {
-        let mut x_old = x;
-        let pr = dy << 1;
-        let pru = pr - (dx << 1);
-        let mut p = pr - dx;
-
-        while dx > 0 {
-            x += 1;
-            if p > 0 {
-                line_deets.push((x_old, y, x - x_old, 1)); // x_old, y, x - x_old, 1
-                x_old = x;
-                y += y_inc;
-                p += pru;
-            } else {
-                p += pr;
-            }
-            dx -= 1;
-        }
-        line_deets.push((x_old, y, x2 - x_old + 1, 1)); // x_old, y, x2 - x_old + 1, 1
-    } else {
-        let pr = dx << 1;
-        let mut y_old = y;
-        let pru = pr - (dy << 1);
-        let mut p = pr - dy;
-
-        if y2 <= y1 {
-            while dy > 0 {
-                if p > 0 {
-                    line_deets.push((x, y, 1, y_old - y + 1)); // x++, y, 1, y_old - y + 1
-                    x += 1;
-                    y_old = y;
-                    y += y_inc;
-                    p += pru;
-                } else {
-                    y += y_inc;
-                    p += pr;
-                }
+    text.push_str("rects:\n");
+    text.push_str(&css(&glyph.rects));
 
-                dy -= 1;
-            }
+    text.push_str("as drawn:\n");
+    text.push_str(&ascii::draw(&glyph.rects, &arcs));
 
-            line_deets.push((x2, y2, 1, y_old - y2 + 1)); // x2, y2, 1, y_old - y2 + 1
-        } else {
-            while dy > 0 {
-                y += y_inc;
-                if p > 0 {
-                    line_deets.push((x, y_old, 1, y - y_old)); // x++, y_old, 1, y - y_old
-                    x += 1;
-                    y_old = y;
-                    p += pru;
-                } else {
-                    p += pr;
-                }
+    text.push_str(&format!("upright (turned back {} degrees):\n", glyph.angle));
+    text.push_str(&ascii::draw_upright(&glyph.rects, &arcs, glyph.angle));
 
-                dy -= 1;
-            }
+    text
+}
 
-            line_deets.push((x2, y_old, 1, y2 - y_old + 1)); // x2, y_old, 1, y2 - y_old + 1
+// the same format the page uses for its divs, so it can be pasted straight into a browser
+fn css(rects: &[Rect]) -> String {
+    rects
+        .iter()
+        .map(|i| {
+            format!(
+                "left:{}px;top:{}px;width:{}px;height:{}px;\n",
+                i.0, i.1, i.2, i.3
+            )
+        })
+        .collect()
+}
+
+fn write_output(path: &Option<PathBuf>, text: &str) -> std::io::Result<()> {
+    match path {
+        Some(path) => fs::write(path, text),
+        None => {
+            println!("{}", text);
+            Ok(())
         }
     }
-
-    return Ok(line_deets);
 }
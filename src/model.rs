@@ -0,0 +1,464 @@
+// a recogniser that learns from examples instead of following rules. every glyph becomes one long list of numbers
+// (its upright grid, where its arcs are in that grid, and its moment descriptor) and a linear softmax classifier is
+// fitted to them by stochastic gradient descent. the examples come from synthetic pages, where what every glyph is
+// is known, and from real pages someone has checked the answer for. the model is a json file with a version in it,
+// so an old one is turned away rather than read wrong
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    error::Error,
+    fmt, fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    analyze::CharacterID,
+    bitmap::{GlyphBitmap, SIZE},
+    decode_page,
+    draw::commands,
+    glyph::Glyph,
+    moments::{Descriptor, RINGS},
+    rasterize, segment,
+    synth::{generate, splitmix, Layout},
+    HTSError,
+};
+
+// bumped whenever features() or the file's layout changes, since a model only makes sense with the features it was
+// trained on
+pub const VERSION: u32 = 1;
+
+// how many numbers features() gives
+pub const FEATURES: usize = 2 * SIZE * SIZE + 7 + RINGS + 1;
+
+const EPOCHS: usize = 40;
+const RATE: f64 = 0.05;
+// how fast the rate comes down, per pass over the examples
+const DECAY: f64 = 0.1;
+// pulls every weight towards 0 a little, so nothing leans on one pixel
+const L2: f64 = 1e-4;
+
+// every synthetic page should decode, so more than a few that don't means something's broken and a model trained on
+// the rest wouldn't be worth keeping
+const MIN_PAGES: f64 = 0.9;
+
+// a glyph's features and what it really is
+#[derive(Debug, Clone)]
+pub struct Example {
+    pub features: Vec<f64>,
+    pub label: CharacterID,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Model {
+    pub version: u32,
+    pub classes: Vec<CharacterID>,
+    // every feature is moved and scaled by these before it's weighed, so they all count on the same scale
+    means: Vec<f64>,
+    scales: Vec<f64>,
+    // one row per class, a weight per feature and then the bias
+    weights: Vec<Vec<f64>>,
+}
+
+#[derive(Debug)]
+pub enum ModelError {
+    Io { path: PathBuf, source: io::Error },
+    Format(serde_json::Error),
+    Version { found: u32 },
+    // the model was trained on a different number of features than features() gives
+    Shape { found: usize },
+    // there isn't one row of weights for every class
+    Classes { classes: usize, rows: usize },
+    Page(HTSError),
+    Synthetic { seed: u64, source: HTSError },
+    // too many synthetic pages couldn't be used to train on
+    TooFewPages { used: usize, asked: usize },
+    // the corrected answer doesn't have one character for every glyph the page decoded to
+    Labels { glyphs: usize, answer: usize },
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ModelError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            ModelError::Format(e) => write!(f, "not a model file: {}", e),
+            ModelError::Version { found } => {
+                write!(
+                    f,
+                    "the model is version {} but this build reads version {}, retrain it",
+                    found, VERSION
+                )
+            }
+            ModelError::Shape { found } => {
+                write!(
+                    f,
+                    "the model has {} features but glyphs have {}, retrain it",
+                    found, FEATURES
+                )
+            }
+            ModelError::Classes { classes, rows } => {
+                write!(
+                    f,
+                    "the model has {} classes but weights for {}, retrain it",
+                    classes, rows
+                )
+            }
+            ModelError::Page(e) => write!(f, "decoding a corrected page: {}", e),
+            ModelError::Synthetic { seed, source } => {
+                write!(f, "segmenting synthetic page {}: {}", seed, source)
+            }
+            ModelError::TooFewPages { used, asked } => {
+                write!(
+                    f,
+                    "only {} of {} synthetic pages could be trained on",
+                    used, asked
+                )
+            }
+            ModelError::Labels { glyphs, answer } => {
+                write!(
+                    f,
+                    "the page has {} characters but the answer has {}",
+                    glyphs, answer
+                )
+            }
+        }
+    }
+}
+
+impl Error for ModelError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ModelError::Io { source, .. } => Some(source),
+            ModelError::Format(e) => Some(e),
+            ModelError::Page(e) => Some(e),
+            ModelError::Synthetic { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// how a model did on examples it wasn't trained on
+#[derive(Debug, Clone, Default)]
+pub struct Evaluation {
+    pub right: usize,
+    pub total: usize,
+    // (what it was, what it was read as) for every mistake, and how many times
+    pub confusions: BTreeMap<(char, char), usize>,
+}
+
+impl Evaluation {
+    pub fn accuracy(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+
+        self.right as f64 / self.total as f64
+    }
+}
+
+// what the model looks at: the glyph turned upright and its arcs, as 0s and 1s a cell, then its moment descriptor
+pub fn features(glyph: &Glyph, arcs: &HashSet<(i32, i32)>) -> Vec<f64> {
+    let cells = |bitmap: GlyphBitmap| {
+        (0..SIZE as i32)
+            .flat_map(move |y| (0..SIZE as i32).map(move |x| f64::from(u8::from(bitmap.get(x, y)))))
+    };
+
+    let mut features: Vec<f64> = cells(GlyphBitmap::of(glyph))
+        .chain(cells(GlyphBitmap::arcs_of(glyph, arcs)))
+        .collect();
+
+    match Descriptor::of(glyph, arcs) {
+        Some(descriptor) => {
+            features.extend(descriptor.hu);
+            features.extend(descriptor.radial);
+            features.push(descriptor.arc_share);
+        }
+        None => features.resize(FEATURES, 0.0),
+    }
+
+    features
+}
+
+// every glyph on a synthetic page. each one is labelled by whichever character was drawn nearest it and turned the
+// way that character was, so a page the reading order gets wrong still gives the right labels
+pub fn synthetic_examples(seed: u64) -> Result<Vec<Example>, ModelError> {
+    let page = generate(seed, Layout::REALISTIC);
+    let failed = |source: HTSError| ModelError::Synthetic { seed, source };
+
+    let commands = commands(&page.draw_data).map_err(|e| failed(e.into()))?;
+    let (curves, lines) = rasterize(&commands).map_err(|e| failed(e.into()))?;
+    let glyphs = segment(&curves, &lines).map_err(|e| failed(e.into()))?;
+    let arcs: HashSet<(i32, i32)> = curves.iter().map(|i| (i.0, i.1)).collect();
+
+    Ok(glyphs
+        .into_iter()
+        .filter_map(|mut glyph| {
+            let (index, _) = page.places.iter().enumerate().min_by(|a, b| {
+                let distance =
+                    |i: &(f64, f64)| (i.0 - glyph.centroid.0).hypot(i.1 - glyph.centroid.1);
+                distance(a.1).total_cmp(&distance(b.1))
+            })?;
+            let label = CharacterID::of(page.text.chars().nth(index)?)?;
+            glyph.move_to(index as i32);
+
+            Some(Example {
+                features: features(&glyph, &arcs),
+                label,
+            })
+        })
+        .collect())
+}
+
+// synthetic_examples from every seed in seeds. a page that can't be used is logged and left out, and it's an error if
+// too many of them are
+pub fn synthetic_examples_from(seeds: Range<u64>) -> Result<Vec<Example>, ModelError> {
+    let (mut used, mut asked) = (0, 0);
+    let mut examples = Vec::new();
+
+    for seed in seeds {
+        asked += 1;
+        match synthetic_examples(seed) {
+            Ok(mut page) => {
+                used += 1;
+                examples.append(&mut page);
+            }
+            Err(e) => warn!("leaving out {}", e),
+        }
+    }
+
+    if (used as f64) < (asked as f64) * MIN_PAGES {
+        return Err(ModelError::TooFewPages { used, asked });
+    }
+
+    Ok(examples)
+}
+
+// every glyph on a real page, labelled with the answer someone has checked it should decode to
+pub fn page_examples(page: &str, answer: &str) -> Result<Vec<Example>, ModelError> {
+    let decoded = decode_page(page).map_err(ModelError::Page)?;
+    let answer: Vec<char> = answer.trim().chars().collect();
+
+    if answer.len() != decoded.glyphs.len() {
+        return Err(ModelError::Labels {
+            glyphs: decoded.glyphs.len(),
+            answer: answer.len(),
+        });
+    }
+
+    let arcs: HashSet<(i32, i32)> = decoded.curves.iter().map(|i| (i.0, i.1)).collect();
+
+    Ok(decoded
+        .glyphs
+        .iter()
+        .zip(answer)
+        .filter_map(|(glyph, c)| {
+            Some(Example {
+                features: features(glyph, &arcs),
+                label: CharacterID::of(c)?,
+            })
+        })
+        .collect())
+}
+
+// the examples shuffled the same way every time, with about holdout of them kept back for testing on
+pub fn split(mut examples: Vec<Example>, holdout: f64) -> (Vec<Example>, Vec<Example>) {
+    shuffle(&mut examples, 0);
+
+    let kept = ((examples.len() as f64) * holdout.clamp(0.0, 1.0)).round() as usize;
+    let training = examples.split_off(kept);
+
+    (training, examples)
+}
+
+impl Model {
+    pub fn train(examples: &[Example]) -> Model {
+        let mut classes: Vec<CharacterID> = examples.iter().map(|i| i.label).collect();
+        classes.sort_by_key(|i| i.value());
+        classes.dedup();
+
+        let n = examples.len().max(1) as f64;
+        let means: Vec<f64> = (0..FEATURES)
+            .map(|k| examples.iter().map(|i| i.features[k]).sum::<f64>() / n)
+            .collect();
+        let scales: Vec<f64> = (0..FEATURES)
+            .map(|k| {
+                let variance = examples
+                    .iter()
+                    .map(|i| (i.features[k] - means[k]).powi(2))
+                    .sum::<f64>()
+                    / n;
+                variance.sqrt().max(1e-3)
+            })
+            .collect();
+
+        let mut model = Model {
+            version: VERSION,
+            classes,
+            means,
+            scales,
+            weights: Vec::new(),
+        };
+        model.weights = vec![vec![0.0; FEATURES + 1]; model.classes.len()];
+
+        let inputs: Vec<(Vec<f64>, usize)> = examples
+            .iter()
+            .filter_map(|i| {
+                Some((
+                    model.standardised(&i.features),
+                    model.classes.iter().position(|c| *c == i.label)?,
+                ))
+            })
+            .collect();
+        let mut order: Vec<usize> = (0..inputs.len()).collect();
+
+        for epoch in 0..EPOCHS {
+            shuffle(&mut order, epoch as u64 + 1);
+            let rate = RATE / (1.0 + DECAY * epoch as f64);
+
+            for (x, label) in order.iter().map(|i| &inputs[*i]) {
+                let p = model.probabilities(x);
+
+                for (class, row) in model.weights.iter_mut().enumerate() {
+                    let gradient = p[class] - f64::from(u8::from(class == *label));
+
+                    for (w, x) in row.iter_mut().zip(x.iter().chain(std::iter::once(&1.0))) {
+                        *w -= rate * (gradient * x + L2 * *w);
+                    }
+                }
+            }
+        }
+
+        model
+    }
+
+    pub fn load(path: &Path) -> Result<Model, ModelError> {
+        let text = fs::read_to_string(path).map_err(|source| ModelError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Model::from_json(&text)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ModelError> {
+        fs::write(path, self.to_json()?).map_err(|source| ModelError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    // the version is checked before anything else is, so an old file says it's old rather than that it's broken
+    pub fn from_json(text: &str) -> Result<Model, ModelError> {
+        #[derive(Deserialize)]
+        struct Versioned {
+            version: u32,
+        }
+
+        let versioned: Versioned = serde_json::from_str(text).map_err(ModelError::Format)?;
+        if versioned.version != VERSION {
+            return Err(ModelError::Version {
+                found: versioned.version,
+            });
+        }
+
+        let model: Model = serde_json::from_str(text).map_err(ModelError::Format)?;
+        if model.weights.len() != model.classes.len() {
+            return Err(ModelError::Classes {
+                classes: model.classes.len(),
+                rows: model.weights.len(),
+            });
+        }
+
+        // every row has a bias after its weights
+        if let Some(found) = [model.means.len(), model.scales.len()]
+            .into_iter()
+            .find(|i| *i != FEATURES)
+            .or_else(|| {
+                model
+                    .weights
+                    .iter()
+                    .find(|i| i.len() != FEATURES + 1)
+                    .map(|i| i.len().saturating_sub(1))
+            })
+        {
+            return Err(ModelError::Shape { found });
+        }
+
+        Ok(model)
+    }
+
+    pub fn to_json(&self) -> Result<String, ModelError> {
+        serde_json::to_string(self).map_err(ModelError::Format)
+    }
+
+    // every class and how likely the model thinks it is, most likely first
+    pub fn classify(&self, features: &[f64]) -> Vec<(CharacterID, f64)> {
+        let p = self.probabilities(&self.standardised(features));
+        let mut classes: Vec<(CharacterID, f64)> = self.classes.iter().copied().zip(p).collect();
+
+        classes.sort_by(|a, b| b.1.total_cmp(&a.1));
+        classes
+    }
+
+    pub fn evaluate(&self, examples: &[Example]) -> Evaluation {
+        let mut evaluation = Evaluation::default();
+
+        for example in examples {
+            let Some(&(read, _)) = self.classify(&example.features).first() else {
+                continue;
+            };
+
+            evaluation.total += 1;
+            if read == example.label {
+                evaluation.right += 1;
+            } else {
+                *evaluation
+                    .confusions
+                    .entry((example.label.value(), read.value()))
+                    .or_insert(0) += 1;
+            }
+        }
+
+        evaluation
+    }
+
+    fn standardised(&self, features: &[f64]) -> Vec<f64> {
+        features
+            .iter()
+            .zip(self.means.iter().zip(self.scales.iter()))
+            .map(|(x, (mean, scale))| (x - mean) / scale)
+            .collect()
+    }
+
+    // softmax over every class's weighted sum, x already standardised
+    fn probabilities(&self, x: &[f64]) -> Vec<f64> {
+        let scores: Vec<f64> = self
+            .weights
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(x.iter().chain(std::iter::once(&1.0)))
+                    .map(|(w, x)| w * x)
+                    .sum()
+            })
+            .collect();
+
+        let top = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let exp: Vec<f64> = scores.iter().map(|i| (i - top).exp()).collect();
+        let total: f64 = exp.iter().sum();
+
+        exp.iter().map(|i| i / total).collect()
+    }
+}
+
+// fisher-yates with splitmix, so the same seed always gives the same order
+fn shuffle<T>(items: &mut [T], seed: u64) {
+    let mut state = seed;
+
+    for i in (1..items.len()).rev() {
+        let j = (splitmix(&mut state) % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
@@ -0,0 +1,205 @@
+// shape descriptors that come out the same however far round a glyph is turned, so a glyph can be read without
+// trusting the angle its place in the reading order gives it. hu's seven moment invariants for the overall shape, how
+// the ink is spread out from the middle, and how much of it is arcs. a glyph is read as whichever character from a
+// set of references drawn at every turn it's closest to
+
+use std::{collections::HashSet, sync::OnceLock};
+
+use serde::Serialize;
+
+use crate::{
+    analyze::{is_arc, CharacterID},
+    draw::Rect,
+    glyph::Glyph,
+    synth::{character, ALPHABET},
+    transform::pixels,
+};
+
+// how many bands the ink's distance from the middle is counted in
+pub const RINGS: usize = 8;
+
+// the bands reach out to this many times the ink's root mean square distance from the middle
+const REACH: f64 = 2.0;
+
+// which root of each hu moment to take so they all grow at the same rate as the shape stretches out, rather than
+// the higher ones vanishing next to the first
+const ROOTS: [f64; 7] = [1.0, 2.0, 2.0, 2.0, 4.0, 3.0, 4.0];
+
+// the references are drawn this far round from each other
+const TURN: i32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Descriptor {
+    pub hu: [f64; 7],
+    // share of the ink in each band out from the middle, innermost first
+    pub radial: [f64; RINGS],
+    pub arc_share: f64,
+}
+
+impl Descriptor {
+    // None for a glyph with no ink
+    pub fn of(glyph: &Glyph, arcs: &HashSet<(i32, i32)>) -> Option<Descriptor> {
+        let ink: Vec<(f64, f64)> = pixels(&glyph.rects)
+            .into_keys()
+            .map(|(x, y)| (f64::from(x), f64::from(y)))
+            .collect();
+        if ink.is_empty() {
+            return None;
+        }
+
+        let n = ink.len() as f64;
+        let (cx, cy) = (
+            ink.iter().map(|i| i.0).sum::<f64>() / n,
+            ink.iter().map(|i| i.1).sum::<f64>() / n,
+        );
+
+        // central moments scaled so the glyph's size drops out
+        let eta = |p: i32, q: i32| {
+            let mu: f64 = ink
+                .iter()
+                .map(|(x, y)| (x - cx).powi(p) * (y - cy).powi(q))
+                .sum();
+            mu / n.powf(1.0 + f64::from(p + q) / 2.0)
+        };
+        let (n20, n02, n11) = (eta(2, 0), eta(0, 2), eta(1, 1));
+        let (n30, n03, n21, n12) = (eta(3, 0), eta(0, 3), eta(2, 1), eta(1, 2));
+
+        let (a, b) = (n30 + n12, n21 + n03);
+        let (c, d) = (n30 - 3.0 * n12, 3.0 * n21 - n03);
+        let hu = [
+            n20 + n02,
+            (n20 - n02).powi(2) + 4.0 * n11 * n11,
+            c * c + d * d,
+            a * a + b * b,
+            c * a * (a * a - 3.0 * b * b) + d * b * (3.0 * a * a - b * b),
+            (n20 - n02) * (a * a - b * b) + 4.0 * n11 * a * b,
+            d * a * (a * a - 3.0 * b * b) - c * b * (3.0 * a * a - b * b),
+        ];
+        let hu: [f64; 7] =
+            std::array::from_fn(|i| hu[i].signum() * hu[i].abs().powf(1.0 / ROOTS[i]));
+
+        let distances: Vec<f64> = ink.iter().map(|(x, y)| (x - cx).hypot(y - cy)).collect();
+        let spread = (distances.iter().map(|i| i * i).sum::<f64>() / n)
+            .sqrt()
+            .max(1.0);
+
+        let mut radial = [0.0; RINGS];
+        for distance in distances {
+            let ring = (distance / spread / REACH * RINGS as f64) as usize;
+            radial[ring.min(RINGS - 1)] += 1.0 / n;
+        }
+
+        let arc_share = glyph.rects.iter().filter(|i| is_arc(i, arcs)).count() as f64
+            / glyph.rects.len() as f64;
+
+        Some(Descriptor {
+            hu,
+            radial,
+            arc_share,
+        })
+    }
+
+    fn values(&self) -> impl Iterator<Item = f64> + '_ {
+        self.hu
+            .iter()
+            .chain(self.radial.iter())
+            .chain(std::iter::once(&self.arc_share))
+            .copied()
+    }
+}
+
+// descriptors of every character drawn at every turn, to be measured against
+#[derive(Debug, Clone)]
+pub struct ReferenceSet {
+    pub references: Vec<(CharacterID, Descriptor)>,
+    // how much each value varies across the whole set, so one that's naturally big doesn't drown the rest out
+    spread: Vec<f64>,
+}
+
+impl ReferenceSet {
+    // the synthetic page's characters, drawn on their own at every turn the mission uses
+    pub fn synthetic() -> ReferenceSet {
+        let mut references = Vec::new();
+
+        for c in ALPHABET.iter() {
+            let Some(id) = CharacterID::of(*c as char) else {
+                continue;
+            };
+
+            for turn in (0..360).step_by(TURN as usize) {
+                // somewhere off the pixel grid, so the references don't all round the same way
+                let at = (
+                    100.0 + f64::from(turn % 7) / 7.0,
+                    100.0 + f64::from(turn % 5) / 5.0,
+                );
+                let (mut arcs, mut rects): (Vec<Rect>, Vec<Rect>) = (Vec::new(), Vec::new());
+
+                for command in character(*c, turn, at) {
+                    let drawn = command.rasterize().unwrap_or_default();
+                    if command.is_line() {
+                        rects.extend(drawn);
+                    } else {
+                        arcs.extend(drawn);
+                    }
+                }
+
+                let curves: HashSet<(i32, i32)> = arcs.iter().map(|i| (i.0, i.1)).collect();
+                let glyph = Glyph::new([arcs, rects].concat(), 0);
+
+                if let Some(descriptor) = Descriptor::of(&glyph, &curves) {
+                    references.push((id, descriptor));
+                }
+            }
+        }
+
+        ReferenceSet::new(references)
+    }
+
+    pub fn new(references: Vec<(CharacterID, Descriptor)>) -> ReferenceSet {
+        let n = references.len().max(1) as f64;
+        let values: Vec<Vec<f64>> = references.iter().map(|i| i.1.values().collect()).collect();
+        let width = values.first().map_or(0, |i| i.len());
+
+        let spread = (0..width)
+            .map(|k| {
+                let mean = values.iter().map(|i| i[k]).sum::<f64>() / n;
+                let variance = values.iter().map(|i| (i[k] - mean).powi(2)).sum::<f64>() / n;
+                variance.sqrt().max(1e-6)
+            })
+            .collect();
+
+        ReferenceSet { references, spread }
+    }
+
+    // built the first time it's asked for
+    pub fn shared() -> &'static ReferenceSet {
+        static SHARED: OnceLock<ReferenceSet> = OnceLock::new();
+        SHARED.get_or_init(ReferenceSet::synthetic)
+    }
+
+    pub fn distance(&self, a: &Descriptor, b: &Descriptor) -> f64 {
+        a.values()
+            .zip(b.values())
+            .zip(self.spread.iter())
+            .map(|((a, b), spread)| ((a - b) / spread).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    // every character's closest reference to descriptor, closest first
+    pub fn nearest(&self, descriptor: &Descriptor) -> Vec<(CharacterID, f64)> {
+        let mut nearest: Vec<(CharacterID, f64)> = Vec::new();
+
+        for (id, reference) in self.references.iter() {
+            let distance = self.distance(descriptor, reference);
+
+            match nearest.iter_mut().find(|i| i.0 == *id) {
+                Some(best) => best.1 = best.1.min(distance),
+                None => nearest.push((*id, distance)),
+            }
+        }
+
+        nearest.sort_by(|a, b| a.1.total_cmp(&b.1));
+        nearest
+    }
+}
@@ -0,0 +1,325 @@
+// how far round a glyph is turned, worked out from its own shape rather than from where it comes in the reading order.
+// the glyph's ink is turned back by every angle in turn and laid over an upright template of every character, and
+// whichever angle it lands closest to a template at is how far it's turned. one glyph missed by segmentation leaves
+// every angle after it 10 degrees out, which nothing that trusts the reading order can see
+
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+use crate::{
+    analyze::CharacterID,
+    frame::Frame,
+    glyph::Glyph,
+    synth::{character, ALPHABET},
+    transform::pixels,
+};
+
+// degrees between the angles tried at first, and then either side of the ones that fit best
+const COARSE: i32 = 4;
+const FINE: i32 = 2;
+
+// a fit this much worse than the best is as good as it as far as a few pixels can tell. a 0 or an 8 looks much the
+// same either way up, and a 6 is a 9 turned round, so there can be more than one angle that fits. any tighter and
+// some of them on clean pages come out the wrong way up
+const SLACK: f64 = 1.2;
+
+// how far the shape and the reading order can disagree before it counts. a handful of whole pixels only pins the turn
+// down to within about 15 degrees, that being the worst over a dozen clean synthetic pages, so one glyph's shape can
+// see the reading order slip by two characters but not by one
+pub const TOLERANCE: f64 = 16.0;
+
+// how far the spiral can put a glyph from the angle the reading order gives it and still vouch for it. half the 10
+// degrees between characters, where clean pages come in under a degree
+const PLACED: f64 = 5.0;
+
+// the distance maps cover this far either side of the middle, in spreads, at CELLS cells a spread
+const REACH: f64 = 3.0;
+const CELLS: f64 = 8.0;
+
+// the templates are made from every character drawn at every turn this far apart
+const TURN: i32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Orientation {
+    // degrees anticlockwise the shape says the glyph is turned
+    pub angle: f64,
+    // the template it fitted at that angle
+    pub character: CharacterID,
+    // how far, on average, its ink and the template's were from each other, in spreads
+    pub misfit: f64,
+    // how far angle is from the one the reading order gave, whichever way round is shorter
+    pub disagreement: f64,
+}
+
+impl Orientation {
+    // None for a glyph with no ink
+    pub fn of(glyph: &Glyph) -> Option<Orientation> {
+        let ink = Shape::of(
+            pixels(&glyph.rects)
+                .into_keys()
+                .map(|(x, y)| (f64::from(x), f64::from(y)))
+                .collect(),
+        )?;
+
+        // the best fit at every few degrees, then closer in around every angle that fits about as well as the best
+        // and better than the angles either side of it. and around the reading order's angle too, since a shape
+        // that fits either way up can come out a hair worse the right way up than the wrong one
+        let ring = glyph.frame.rotation;
+        let coarse: Vec<Trial> = (0..360)
+            .step_by(COARSE as usize)
+            .filter_map(|i| ink.best_fit(f64::from(i)))
+            .collect();
+        let fine: Vec<Trial> = fits(&coarse)
+            .map(|i| i.0)
+            .filter_map(|angle| {
+                (-COARSE + FINE..COARSE)
+                    .step_by(FINE as usize)
+                    .filter_map(|i| ink.best_fit((angle + f64::from(i)).rem_euclid(360.0)))
+                    .min_by(|a, b| a.2.total_cmp(&b.2))
+                    .map(|i| ink.between(i))
+            })
+            .collect();
+
+        // of those, the one nearest what the reading order says is the one to go with, so a shape that fits either
+        // way up only disagrees if neither way is close
+        let best = fine.iter().map(|i| i.2).min_by(f64::total_cmp)?;
+        let (angle, id, misfit) = fine
+            .into_iter()
+            .filter(|i| i.2 <= best * SLACK)
+            .min_by(|a, b| apart(a.0, ring).total_cmp(&apart(b.0, ring)))?;
+
+        Some(Orientation {
+            angle,
+            character: id,
+            misfit,
+            disagreement: apart(angle, ring),
+        })
+    }
+
+    // of(), but only for a glyph the spiral doesn't already put where the reading order says. the shape is slow to
+    // measure and much less sure of the turn than the spiral is, so it only gets asked when there's nothing better
+    pub fn if_needed(glyph: &Glyph) -> Option<Orientation> {
+        match glyph.measured_angle() {
+            Some(angle) if apart(angle, glyph.frame.rotation) <= PLACED => None,
+            _ => Orientation::of(glyph),
+        }
+    }
+
+    pub fn agrees(&self) -> bool {
+        self.disagreement <= TOLERANCE
+    }
+}
+
+// an angle, the template that fits best at it, and how badly
+type Trial = (f64, CharacterID, f64);
+
+// the trials that fit about as well as the best, and better than the ones either side
+fn fits(trials: &[Trial]) -> impl Iterator<Item = Trial> + '_ {
+    let n = trials.len();
+    let best = trials.iter().map(|i| i.2).fold(f64::INFINITY, f64::min);
+
+    (0..n)
+        .filter(move |i| trials[*i].2 <= best * SLACK)
+        .filter(move |i| {
+            trials[*i].2 <= trials[(i + 1) % n].2 && trials[*i].2 <= trials[(i + n - 1) % n].2
+        })
+        .map(|i| trials[i])
+}
+
+// degrees between two angles, the short way round
+fn apart(a: f64, b: f64) -> f64 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
+// ink centred on its middle and scaled so its root mean square distance from the middle is 1, and how far every cell
+// of a grid over it is from the nearest of it
+struct Shape {
+    points: Vec<(f64, f64)>,
+    distances: Vec<f64>,
+}
+
+impl Shape {
+    fn of(points: Vec<(f64, f64)>) -> Option<Shape> {
+        let points = Shape::normalised(&points)?;
+        let distances = Shape::distances(&points);
+
+        Some(Shape { points, distances })
+    }
+
+    fn normalised(points: &[(f64, f64)]) -> Option<Vec<(f64, f64)>> {
+        if points.is_empty() {
+            return None;
+        }
+
+        let n = points.len() as f64;
+        let middle = (
+            points.iter().map(|i| i.0).sum::<f64>() / n,
+            points.iter().map(|i| i.1).sum::<f64>() / n,
+        );
+        let spread = (points
+            .iter()
+            .map(|i| (i.0 - middle.0).powi(2) + (i.1 - middle.1).powi(2))
+            .sum::<f64>()
+            / n)
+            .sqrt()
+            .max(1.0);
+
+        Some(
+            points
+                .iter()
+                .map(|i| ((i.0 - middle.0) / spread, (i.1 - middle.1) / spread))
+                .collect(),
+        )
+    }
+
+    // every cell starts as far as the cell's own ink, or unknown, and two passes over the grid carry the nearest
+    // distance across from the cells next to it. a little further than the straight line at some angles, which
+    // doesn't matter when every glyph is measured the same way
+    fn distances(points: &[(f64, f64)]) -> Vec<f64> {
+        let side = Shape::side();
+        let mut distances = vec![f64::INFINITY; side * side];
+        for point in points.iter() {
+            let (x, y) = (Shape::cell(point.0), Shape::cell(point.1));
+            let (cx, cy) = Shape::centre(x, y);
+            let n = y * side + x;
+            distances[n] = distances[n].min((point.0 - cx).hypot(point.1 - cy));
+        }
+
+        let (straight, diagonal) = (1.0 / CELLS, std::f64::consts::SQRT_2 / CELLS);
+        let before = [
+            (-1, 0, straight),
+            (-1, -1, diagonal),
+            (0, -1, straight),
+            (1, -1, diagonal),
+        ];
+        let after = before.map(|(dx, dy, d)| (-dx, -dy, d));
+
+        for (order, around) in [(false, before), (true, after)] {
+            for n in 0..side * side {
+                let n = if order { side * side - 1 - n } else { n };
+                let (x, y) = ((n % side) as i32, (n / side) as i32);
+
+                for (dx, dy, d) in around {
+                    let (x, y) = (x + dx, y + dy);
+                    if (0..side as i32).contains(&x) && (0..side as i32).contains(&y) {
+                        distances[n] =
+                            distances[n].min(distances[y as usize * side + x as usize] + d);
+                    }
+                }
+            }
+        }
+
+        distances
+    }
+
+    fn side() -> usize {
+        (2.0 * REACH * CELLS) as usize
+    }
+
+    fn centre(x: usize, y: usize) -> (f64, f64) {
+        (
+            (x as f64 + 0.5) / CELLS - REACH,
+            (y as f64 + 0.5) / CELLS - REACH,
+        )
+    }
+
+    // the grid cell a coordinate is in, anything off it in the one at the edge
+    fn cell(i: f64) -> usize {
+        (((i + REACH) * CELLS).max(0.0) as usize).min(Shape::side() - 1)
+    }
+
+    // how far point is from the nearest ink
+    fn distance(&self, point: (f64, f64)) -> f64 {
+        self.distances[Shape::cell(point.1) * Shape::side() + Shape::cell(point.0)]
+    }
+
+    // the trial moved to the bottom of a parabola through how it and the angles a step either side fit the same
+    // template, since the best angle is hardly ever a whole step
+    fn between(&self, trial: Trial) -> Trial {
+        let (angle, id, misfit) = trial;
+        let Some((_, template)) = templates().iter().find(|i| i.0 == id) else {
+            return trial;
+        };
+
+        let step = f64::from(FINE);
+        let (before, after) = (
+            self.misfit(template, angle - step),
+            self.misfit(template, angle + step),
+        );
+        let curve = before - 2.0 * misfit + after;
+        if curve <= 0.0 {
+            return trial;
+        }
+
+        let offset = (step * (before - after) / (2.0 * curve)).clamp(-step, step);
+        ((angle + offset).rem_euclid(360.0), id, misfit)
+    }
+
+    fn best_fit(&self, angle: f64) -> Option<Trial> {
+        templates()
+            .iter()
+            .map(|(id, template)| (angle, *id, self.misfit(template, angle)))
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+    }
+
+    // how badly this fits upright over turned back by angle, both ways round so that a template that's only part of
+    // the ink, or the ink only part of it, doesn't fit well
+    fn misfit(&self, upright: &Shape, angle: f64) -> f64 {
+        let frame = Frame::new((0.0, 0.0), angle);
+
+        let there = self
+            .points
+            .iter()
+            .map(|i| upright.distance(frame.to_local(*i)))
+            .sum::<f64>();
+        let back = upright
+            .points
+            .iter()
+            .map(|i| self.distance(frame.to_canvas(*i)))
+            .sum::<f64>();
+
+        there / self.points.len() as f64 + back / upright.points.len() as f64
+    }
+}
+
+// every character drawn on its own at every turn and turned back upright, made the first time it's asked for. the
+// distances are to the ink of all of them, so a glyph doesn't have to have landed on the same pixels as any one turn
+// to fit, and the points are the ones it has upright
+fn templates() -> &'static [(CharacterID, Shape)] {
+    static TEMPLATES: OnceLock<Vec<(CharacterID, Shape)>> = OnceLock::new();
+
+    TEMPLATES.get_or_init(|| {
+        ALPHABET
+            .iter()
+            .filter_map(|c| {
+                let turns: Vec<Vec<(f64, f64)>> = (0..360)
+                    .step_by(TURN as usize)
+                    .filter_map(|turn| {
+                        let at = (
+                            100.0 + f64::from(turn % 7) / 7.0,
+                            100.0 + f64::from(turn % 5) / 5.0,
+                        );
+                        let frame = Frame::new(at, f64::from(turn));
+                        let rects: Vec<_> = character(*c, turn, at)
+                            .iter()
+                            .flat_map(|i| i.rasterize().unwrap_or_default())
+                            .collect();
+
+                        let upright: Vec<(f64, f64)> = pixels(&rects)
+                            .into_keys()
+                            .map(|(x, y)| frame.to_local((f64::from(x), f64::from(y))))
+                            .collect();
+                        Shape::normalised(&upright)
+                    })
+                    .collect();
+
+                let points = turns.first()?.clone();
+                let distances = Shape::distances(&turns.concat());
+
+                Some((CharacterID::of(*c as char)?, Shape { points, distances }))
+            })
+            .collect()
+    })
+}
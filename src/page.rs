@@ -0,0 +1,109 @@
+// helpers for pulling the drawing script apart. shared by the fingerprint check and the js reference backend
+
+// finds every "function name(...) { ... }" inside the page's script tags. braces are matched while skipping over strings and comments
+#[cfg(feature = "js-reference")]
+pub fn extract_functions(page: &str) -> Vec<(String, String)> {
+    script_blocks(page)
+        .into_iter()
+        .flat_map(|script| split_functions(script).0)
+        .collect()
+}
+
+// splits one script into its function declarations and whatever is left over (the drawData array, the drawing loop, etc.)
+pub fn split_functions(script: &str) -> (Vec<(String, String)>, String) {
+    let mut functions = Vec::new();
+    let mut top_level = String::new();
+    let mut rest = script;
+
+    while let Some(start) = rest.find("function ") {
+        let decl = &rest[start..];
+        let name: String = decl["function ".len()..]
+            .trim_start()
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+            .collect();
+
+        match (name.is_empty(), matching_brace_end(decl)) {
+            (false, Some(end)) => {
+                top_level.push_str(&rest[..start]);
+                functions.push((name, decl[..end].to_owned()));
+                rest = &decl[end..];
+            }
+            _ => {
+                let skip = start + "function ".len();
+                top_level.push_str(&rest[..skip]);
+                rest = &rest[skip..];
+            }
+        }
+    }
+
+    top_level.push_str(rest);
+
+    (functions, top_level)
+}
+
+pub fn script_blocks(page: &str) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    let mut rest = page;
+
+    while let Some(open) = rest.find("<script") {
+        let after_open = &rest[open..];
+
+        let body_start = match after_open.find('>') {
+            Some(i) => i + 1,
+            None => break,
+        };
+
+        let body = &after_open[body_start..];
+        let body_end = body.find("</script>").unwrap_or(body.len());
+
+        blocks.push(&body[..body_end]);
+        rest = &body[body_end..];
+    }
+
+    blocks
+}
+
+// returns the index just past the closing brace of the first {...} block in source
+fn matching_brace_end(source: &str) -> Option<usize> {
+    let bytes = source.as_bytes();
+    let mut depth = 0;
+    let mut i = source.find('{')?;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            quote @ (b'"' | b'\'') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i + 1 < bytes.len() && !(bytes[i] == b'*' && bytes[i + 1] == b'/') {
+                    i += 1;
+                }
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    None
+}
@@ -0,0 +1,212 @@
+// runs the page's own drawing script in an embedded js engine so the rust translations in main.rs can be checked against it.
+// only compiled with the "js-reference" feature. everything runs inside boa, nothing touches the network.
+
+use std::fmt;
+
+use boa_engine::{Context, Source};
+
+use crate::{
+    draw::{DrawCommand, Rect},
+    page::extract_functions,
+};
+
+// stands in for the browser. the page either document.write()s the divs or builds them with createElement, so both are captured.
+// whichever way the div gets made, only its left/top/width/height end up in __rects
+const DOM_SHIM: &str = r#"
+var __rects = [];
+
+function __record(css) {
+    var grab = function (prop) {
+        var m = new RegExp(prop + "\\s*:\\s*(-?\\d+)").exec(css);
+        return m ? parseInt(m[1], 10) : null;
+    };
+    var l = grab("left"), t = grab("top"), w = grab("width"), h = grab("height");
+    if (l !== null && t !== null && w !== null && h !== null) {
+        __rects.push(l + "," + t + "," + w + "," + h);
+    }
+}
+
+function __recordHtml(html) {
+    var re = /style\s*=\s*["']([^"']*)["']/g, m;
+    while ((m = re.exec(html)) !== null) {
+        __record(m[1]);
+    }
+}
+
+function __element() {
+    var el = { style: {}, attributes: {}, children: [] };
+    el.setAttribute = function (k, v) { el.attributes[k] = v; if (k == "style") { el.style.cssText = v; } };
+    el.appendChild = function (c) { __append(c); return c; };
+    return el;
+}
+
+function __append(el) {
+    if (el.style && el.style.cssText) {
+        __record(el.style.cssText);
+    } else if (el.style) {
+        __record("left:" + el.style.left + ";top:" + el.style.top + ";width:" + el.style.width + ";height:" + el.style.height);
+    }
+    if (el.innerHTML) {
+        __recordHtml(el.innerHTML);
+    }
+}
+
+var document = {
+    write: function (html) { __recordHtml(html); },
+    writeln: function (html) { __recordHtml(html); },
+    createElement: function () { return __element(); },
+    getElementById: function () { return document.body; },
+};
+document.body = __element();
+Object.defineProperty(document.body, "innerHTML", { set: function (html) { __recordHtml(html); }, get: function () { return ""; } });
+var window = { document: document };
+"#;
+
+#[derive(Debug)]
+pub enum ReferenceError {
+    FunctionNotFound(&'static str),
+    Script(String),
+}
+
+impl fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReferenceError::FunctionNotFound(name) => {
+                write!(f, "could not find function {} in the page", name)
+            }
+            ReferenceError::Script(msg) => write!(f, "page script failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ReferenceError {}
+
+// the same command could be drawn differently by the page and by us. index is the command's position in the drawing loop
+#[derive(Debug)]
+pub struct Mismatch {
+    pub index: usize,
+    pub command: DrawCommand,
+    pub native: Vec<Rect>,
+    pub reference: Vec<Rect>,
+}
+
+// no loop in drawArc or drawLine comes anywhere near these. a page whose script spins forever or recurses without end
+// fails the check instead of hanging the decode
+const LOOP_LIMIT: u64 = 1_000_000;
+const RECURSION_LIMIT: usize = 256;
+
+pub struct Reference {
+    context: Context,
+}
+
+impl Reference {
+    // pulls every function declaration out of the page's script so drawArc and drawLine keep any helpers they call
+    pub fn from_page(page: &str) -> Result<Reference, ReferenceError> {
+        let functions = extract_functions(page);
+
+        for required in ["drawArc", "drawLine"] {
+            if !functions.iter().any(|(name, _)| name == required) {
+                return Err(ReferenceError::FunctionNotFound(required));
+            }
+        }
+
+        let mut context = Context::default();
+        context
+            .runtime_limits_mut()
+            .set_loop_iteration_limit(LOOP_LIMIT);
+        context
+            .runtime_limits_mut()
+            .set_recursion_limit(RECURSION_LIMIT);
+        run(&mut context, DOM_SHIM)?;
+
+        for (_, source) in functions.iter() {
+            run(&mut context, source)?;
+        }
+
+        Ok(Reference { context })
+    }
+
+    // draws one command with the page's own function and returns the divs it made
+    fn draw(&mut self, call: String) -> Result<Vec<Rect>, ReferenceError> {
+        run(&mut self.context, "__rects = [];")?;
+        run(&mut self.context, &call)?;
+
+        let joined = run(&mut self.context, "__rects.join(';')")?;
+
+        Ok(joined
+            .split(';')
+            .filter(|i| !i.is_empty())
+            .filter_map(|i| {
+                let nums: Vec<i32> = i.split(',').filter_map(|n| n.parse().ok()).collect();
+                match nums[..] {
+                    [l, t, w, h] => Some((l, t, w, h)),
+                    _ => None,
+                }
+            })
+            .collect())
+    }
+
+    // returns (curves, lines) just like draw::rasterize
+    pub fn rasterize(
+        &mut self,
+        commands: &[DrawCommand],
+    ) -> Result<(Vec<Rect>, Vec<Rect>), ReferenceError> {
+        let mut curves = Vec::new();
+        let mut lines = Vec::new();
+
+        for command in commands {
+            let mut rects = self.draw(call_for(command))?;
+
+            if command.is_line() {
+                lines.append(&mut rects);
+            } else {
+                curves.append(&mut rects);
+            }
+        }
+
+        Ok((curves, lines))
+    }
+
+    // runs every command through both backends and reports the ones that disagree
+    pub fn cross_check(
+        &mut self,
+        commands: &[DrawCommand],
+    ) -> Result<Vec<Mismatch>, ReferenceError> {
+        let mut mismatches = Vec::new();
+
+        for (index, command) in commands.iter().copied().enumerate() {
+            // a command we refuse to draw counts as drawing nothing, so it shows up as a mismatch
+            let native = command.rasterize().unwrap_or_default();
+            let reference = self.draw(call_for(&command))?;
+
+            if native != reference {
+                mismatches.push(Mismatch {
+                    index,
+                    command,
+                    native,
+                    reference,
+                });
+            }
+        }
+
+        Ok(mismatches)
+    }
+}
+
+fn call_for(command: &DrawCommand) -> String {
+    match *command {
+        DrawCommand::Arc { x, y, r, s, e } => format!("drawArc({},{},{},{},{});", x, y, r, s, e),
+        DrawCommand::Line { x1, y1, x2, y2 } => format!("drawLine({},{},{},{});", x1, y1, x2, y2),
+    }
+}
+
+fn run(context: &mut Context, source: &str) -> Result<String, ReferenceError> {
+    let value = context
+        .eval(Source::from_bytes(source))
+        .map_err(|e| ReferenceError::Script(e.to_string()))?;
+
+    Ok(match value.as_string() {
+        Some(s) => s.to_std_string_escaped(),
+        None => String::new(),
+    })
+}
@@ -0,0 +1,155 @@
+// everything a decode worked out, in a shape that can be written out as json. this is what the solve rate tracking
+// reads, so fields only ever get added, never renamed
+
+use serde::Serialize;
+
+use crate::{
+    analyze::CharacterID,
+    glyph::Glyph,
+    orientation::Orientation,
+    segment::Section,
+    spiral::{Misfit, Spiral, SpiralLayout},
+    timing::StageTiming,
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodeReport {
+    pub answer: String,
+    pub glyphs: Vec<GlyphReport>,
+    // None when there were fewer than three characters to fit a circle through
+    pub ring: Option<Ring>,
+    // None when ordering couldn't fit one
+    pub spiral: Option<Spiral>,
+    pub misfits: Vec<Misfit>,
+    pub timings: Vec<StageTiming>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GlyphReport {
+    pub index: i32,
+    // (left, top, width, height)
+    pub bounding_box: (i32, i32, i32, i32),
+    pub centroid: (f64, f64),
+    // the angle segmentation assigned from the reading order
+    pub angle: i32,
+    // the angle it sits at around the spiral's centre, see Glyph::measured_angle. None when there's no spiral
+    pub measured_angle: Option<f64>,
+    pub section: Section,
+    // where the character sits on the spiral, when there is one
+    pub turn: Option<i32>,
+    pub along: Option<f64>,
+    pub spiral_residual: Option<f64>,
+    pub id: CharacterID,
+    pub value: String,
+    pub confidence: f64,
+    pub alternatives: Vec<CharacterID>,
+    // how far round the character's own shape says it's turned, to hold angle up against
+    pub orientation: Option<Orientation>,
+}
+
+// the circle that fits the character centroids best
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Ring {
+    pub centre: (f64, f64),
+    pub radius: f64,
+    // distance of the closest and furthest character from the centre
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+}
+
+impl DecodeReport {
+    // glyphs that haven't been read are left out
+    pub fn new(
+        glyphs: &[Glyph],
+        layout: Option<&SpiralLayout>,
+        timings: Vec<StageTiming>,
+    ) -> DecodeReport {
+        let centres: Vec<(f64, f64)> = glyphs
+            .iter()
+            .filter(|i| !i.rects.is_empty())
+            .map(|i| i.centroid)
+            .collect();
+        let ring = Ring::fit(&centres);
+
+        let read: Vec<GlyphReport> = glyphs
+            .iter()
+            .filter_map(|glyph| {
+                let recognition = glyph.recognition.as_ref()?;
+
+                Some(GlyphReport {
+                    index: glyph.index,
+                    bounding_box: glyph.bounding_box,
+                    centroid: glyph.centroid,
+                    angle: glyph.angle,
+                    measured_angle: glyph.measured_angle(),
+                    section: glyph.section,
+                    turn: glyph.placement.map(|i| i.turn),
+                    along: glyph.placement.map(|i| i.along),
+                    spiral_residual: glyph.placement.map(|i| i.residual),
+                    id: recognition.character.id,
+                    value: recognition.character.val.clone(),
+                    confidence: recognition.confidence,
+                    alternatives: recognition.alternatives.clone(),
+                    orientation: recognition.orientation,
+                })
+            })
+            .collect();
+
+        DecodeReport {
+            answer: read.iter().map(|i| i.value.as_str()).collect(),
+            glyphs: read,
+            ring,
+            spiral: layout.map(|i| i.spiral),
+            misfits: layout.map(|i| i.misfits.clone()).unwrap_or_default(),
+            timings,
+        }
+    }
+}
+
+impl Ring {
+    // algebraic (kasa) least squares circle fit. points are moved so their mean is the origin first, which keeps the
+    // sums small and the 3x3 system well behaved
+    pub fn fit(points: &[(f64, f64)]) -> Option<Ring> {
+        if points.len() < 3 {
+            return None;
+        }
+
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|i| i.0).sum::<f64>() / n;
+        let mean_y = points.iter().map(|i| i.1).sum::<f64>() / n;
+
+        let (mut xx, mut xy, mut yy, mut xz, mut yz, mut z) = (0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+
+        for (x, y) in points.iter().map(|i| (i.0 - mean_x, i.1 - mean_y)) {
+            let zz = x * x + y * y;
+            xx += x * x;
+            xy += x * y;
+            yy += y * y;
+            xz += x * zz;
+            yz += y * zz;
+            z += zz;
+        }
+
+        // with the mean at the origin the x and y sums are zero, so the system splits into a 2x2 for the centre
+        let det = xx * yy - xy * xy;
+        if det.abs() < f64::EPSILON {
+            return None;
+        }
+
+        let cx = (xz * yy - yz * xy) / (2.0 * det);
+        let cy = (yz * xx - xz * xy) / (2.0 * det);
+        let radius = (cx * cx + cy * cy + z / n).sqrt();
+
+        let centre = (cx + mean_x, cy + mean_y);
+        let distances = points
+            .iter()
+            .map(|i| ((i.0 - centre.0).powi(2) + (i.1 - centre.1).powi(2)).sqrt());
+
+        Some(Ring {
+            centre,
+            radius,
+            inner_radius: distances.clone().fold(f64::INFINITY, f64::min),
+            outer_radius: distances.fold(0.0, f64::max),
+        })
+    }
+}
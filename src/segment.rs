@@ -0,0 +1,472 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+};
+
+use serde::Serialize;
+use tracing::{debug, debug_span, trace, warn};
+
+use crate::{
+    draw::Rect,
+    glyph::Glyph,
+    spiral::{Placement, Spiral, SpiralLayout},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SegmentationError {
+    EmptyDrawing,
+    // the walk ran out of rects in the direction the section points. glyph is the character it was looking for
+    CouldNotFindNext {
+        glyph: i32,
+        angle: i32,
+        section: Section,
+    },
+}
+
+impl fmt::Display for SegmentationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SegmentationError::EmptyDrawing => write!(f, "the page didn't draw anything"),
+            SegmentationError::CouldNotFindNext {
+                glyph,
+                angle,
+                section,
+            } => write!(
+                f,
+                "unable to find character {} (angle {}, section {:?}) after the one before it",
+                glyph, angle, section
+            ),
+        }
+    }
+}
+
+impl Error for SegmentationError {}
+
+// the characters don't make up one unbroken reading order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderingError {
+    Duplicate { glyph: i32 },
+    Missing { glyph: i32 },
+}
+
+impl fmt::Display for OrderingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderingError::Duplicate { glyph } => write!(f, "two characters claim index {}", glyph),
+            OrderingError::Missing { glyph } => write!(f, "no character has index {}", glyph),
+        }
+    }
+}
+
+impl Error for OrderingError {}
+
+// walks the circle and splits the rects up into characters, in reading order
+pub fn segment(curves: &[Rect], lines: &[Rect]) -> Result<Vec<Glyph>, SegmentationError> {
+    let mut all_coordinates = curves.to_vec();
+    all_coordinates.extend_from_slice(lines);
+
+    // get one of the coordinates from the first top-most character
+    let top = *all_coordinates
+        .iter()
+        .min_by_key(|i| i.1)
+        .ok_or(SegmentationError::EmptyDrawing)?;
+
+    // built once, every character is grown out of this
+    let index = RectIndex::new(&all_coordinates);
+
+    // which of index's rects some character already claimed. the walk and the growing both skip these
+    let mut known = vec![false; index.len()];
+
+    let first_char = {
+        let _glyph = debug_span!("glyph", index = 0, angle = 0, section = ?Section::A).entered();
+        let first_char = index.grow(&[top], &mut known);
+        debug!(rects = first_char.len(), top = top.1, "first character");
+        trace!(rects = ?first_char);
+        first_char
+    };
+
+    let mut prev_coord = *first_char.first().unwrap_or(&top);
+    let mut left_over = index.len() - first_char.len();
+
+    let mut glyphs: Vec<Glyph> = Vec::with_capacity(253);
+    glyphs.push(Glyph::new(first_char, 0));
+
+    // read in circular. after every ninth iteration, the section changes. there are 253 characters. looping 252 times because the first char is already provided
+    for i in 1..253 {
+        let (angle, section) = position(i);
+        let _glyph = debug_span!("glyph", index = i, angle, section = ?section).entered();
+
+        let unclaimed = index
+            .rects()
+            .iter()
+            .zip(known.iter())
+            .filter(|x| !*x.1)
+            .map(|x| x.0);
+        let next_coord = section.get_next(&[prev_coord], unclaimed).ok_or(
+            SegmentationError::CouldNotFindNext {
+                glyph: i,
+                angle,
+                section,
+            },
+        )?;
+
+        prev_coord = next_coord;
+
+        let all_coord_of_next = index.grow(&[next_coord], &mut known);
+        left_over -= all_coord_of_next.len();
+
+        debug!(start = ?next_coord, rects = all_coord_of_next.len(), left_over, "segmented");
+
+        glyphs.push(Glyph::new(all_coord_of_next, i));
+    }
+
+    debug!(section = ?position(252).1, "last section read");
+
+    Ok(glyphs)
+}
+
+// puts the characters back in reading order and gives each one the angle and section its place in that order implies.
+// the indexes the walk gave them have to run 0, 1, 2, ... with nothing missing or doubled up. after that a spiral is
+// fitted through them, and if every character fits it they're put in the order they sit along it. when some don't,
+// or there's no spiral to fit (None), the walk's order stands
+pub fn order(glyphs: &mut [Glyph]) -> Result<Option<SpiralLayout>, OrderingError> {
+    glyphs.sort_by_key(|i| i.index);
+
+    for (expected, glyph) in (0..).zip(glyphs.iter()) {
+        if glyph.index < expected {
+            return Err(OrderingError::Duplicate { glyph: glyph.index });
+        }
+
+        if glyph.index > expected {
+            return Err(OrderingError::Missing { glyph: expected });
+        }
+    }
+
+    // to begin with every character is where the walk says, 10 degrees on from the one before
+    let centres: Option<Vec<(f64, f64)>> = glyphs
+        .iter()
+        .map(|i| (!i.rects.is_empty()).then_some(i.centroid))
+        .collect();
+    let guess: Vec<f64> = glyphs.iter().map(|i| f64::from(i.index * 10)).collect();
+
+    let fitted = centres.and_then(|centres| Spiral::refine(&centres, &guess));
+
+    let layout = fitted.map(|(spiral, placements)| {
+        debug!(?spiral, "fitted spiral");
+
+        for (glyph, placement) in glyphs.iter_mut().zip(placements) {
+            glyph.placement = Some(placement);
+        }
+
+        // positions in glyphs, in the order they sit along the spiral
+        let mut along_spiral: Vec<usize> = (0..glyphs.len()).collect();
+        along_spiral.sort_by(|a, b| along(&glyphs[*a]).total_cmp(&along(&glyphs[*b])));
+
+        let placements: Vec<Placement> = along_spiral
+            .iter()
+            .filter_map(|i| glyphs[*i].placement)
+            .collect();
+        let mut layout = SpiralLayout::new(spiral, &placements);
+
+        if layout.misfits.is_empty() {
+            glyphs.sort_by(|a, b| along(a).total_cmp(&along(b)));
+        } else {
+            // a spiral that doesn't account for every character puts them in a worse order than the walk does, so the
+            // walk's order stands and the misfits are named by their place in it
+            for m in layout.misfits.iter_mut() {
+                m.glyph = glyphs[along_spiral[m.glyph as usize]].index;
+            }
+        }
+
+        layout
+    });
+
+    for (index, glyph) in (0..).zip(glyphs.iter_mut()) {
+        if glyph.index != index {
+            debug!(from = glyph.index, to = index, "moved along the spiral");
+        }
+
+        glyph.move_to(index);
+    }
+
+    for m in layout.iter().flat_map(|i| i.misfits.iter()) {
+        warn!(
+            glyph = m.glyph,
+            turn = m.placement.turn,
+            along = m.placement.along,
+            residual = m.placement.residual,
+            kind = ?m.kind,
+            "character doesn't fit the spiral"
+        );
+    }
+
+    Ok(layout)
+}
+
+fn along(glyph: &Glyph) -> f64 {
+    glyph.placement.map(|i| i.along).unwrap_or_default()
+}
+
+// angle and section of the index-th character. every character is turned 10 degrees further than the one before, and
+// the section moves on every 9 characters, after the character sitting on the boundary has been read
+pub fn position(index: i32) -> (i32, Section) {
+    let sections = [Section::A, Section::B, Section::C, Section::D];
+    let section = if index == 0 { 0 } else { ((index - 1) / 9) % 4 };
+
+    ((index * 10) % 360, sections[section as usize])
+}
+
+// separate the circle into four quarters. ABCD, and go anti-clockwise, starting from the top.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize)]
+pub enum Section {
+    A,
+    B,
+    C,
+    D,
+}
+
+impl Section {
+    // the rect the next character starts from. all_coordinates is every rect no character has claimed yet, it's only
+    // walked once
+    pub fn get_next<'a>(
+        &self,
+        prev: &[(i32, i32, i32, i32)],
+        all_coordinates: impl IntoIterator<Item = &'a (i32, i32, i32, i32)>,
+    ) -> Option<(i32, i32, i32, i32)> {
+        // get random coordinate from the previous character
+        let cc = *prev.first()?;
+        let (cc_x, cc_y) = (cc.0, cc.1);
+
+        trace!(section = ?self, from = ?cc, "looking for the next character");
+
+        let mut next_coordinates: Option<(i32, i32, i32, i32)> = None;
+
+        match self {
+            Section::A => {
+                let mut smallest_y = 50000;
+
+                for i in all_coordinates {
+                    let (x, y) = (i.0, i.1);
+
+                    if x <= cc_x && y >= cc_y && y <= smallest_y {
+                        smallest_y = y;
+                        next_coordinates = Some(*i);
+                    }
+                }
+            }
+            Section::B => {
+                let mut smallest_x = 50000;
+
+                for i in all_coordinates {
+                    let (x, y) = (i.0, i.1);
+
+                    if x >= cc_x && y >= cc_y && x <= smallest_x {
+                        smallest_x = x;
+                        next_coordinates = Some(*i);
+                    }
+                }
+            }
+            Section::C => {
+                let mut largest_y = -50000;
+
+                for i in all_coordinates {
+                    let (x, y) = (i.0, i.1);
+
+                    if x >= cc_x && y <= cc_y && y >= largest_y {
+                        largest_y = y;
+                        next_coordinates = Some(*i);
+                    }
+                }
+            }
+            Section::D => {
+                let mut largest_x = -50000;
+
+                for i in all_coordinates {
+                    let (x, y) = (i.0, i.1);
+
+                    if x <= cc_x && y <= cc_y && x >= largest_x {
+                        largest_x = x;
+                        next_coordinates = Some(*i);
+                    }
+                }
+            }
+        }
+
+        next_coordinates
+    }
+}
+
+// every rect that's connected to initial_coordinates, including them. builds its own index, so when growing more than
+// one character out of the same rects build a RectIndex once and call grow on that instead
+pub fn analyze_character(
+    all_coordinates: &[(i32, i32, i32, i32)],
+    initial_coordinates: &[(i32, i32, i32, i32)],
+) -> Vec<(i32, i32, i32, i32)> {
+    let index = RectIndex::new(all_coordinates);
+    index.grow(initial_coordinates, &mut vec![false; index.len()])
+}
+
+// side of the square buckets rects are sorted into by their top left corner
+const CELL: i32 = 16;
+
+// the page's rects bucketed by position, so finding the ones that touch a rect only looks at the few buckets around it
+// instead of every rect on the page. rects are referred to by where they sit in rects()
+pub struct RectIndex {
+    rects: Vec<(i32, i32, i32, i32)>,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    // the widest and tallest rect, which is how far to the left and above a rect something touching it can start
+    max_w: i32,
+    max_h: i32,
+}
+
+impl RectIndex {
+    pub fn new(all_coordinates: &[(i32, i32, i32, i32)]) -> RectIndex {
+        // the same rect drawn twice only counts once, where it was drawn last. get_next settles ties on the last rect
+        // it sees, so walking rects() keeps it picking the same one
+        let mut seen = HashSet::with_capacity(all_coordinates.len());
+        let mut rects: Vec<(i32, i32, i32, i32)> = all_coordinates
+            .iter()
+            .rev()
+            .filter(|i| seen.insert(**i))
+            .copied()
+            .collect();
+        rects.reverse();
+
+        let mut cells: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+
+        for (id, i) in rects.iter().enumerate() {
+            cells.entry(Self::cell(i.0, i.1)).or_default().push(id);
+        }
+
+        RectIndex {
+            max_w: rects.iter().map(|i| i.2).max().unwrap_or(0).max(0),
+            max_h: rects.iter().map(|i| i.3).max().unwrap_or(0).max(0),
+            rects,
+            cells,
+        }
+    }
+
+    // every distinct rect, in the order the page last drew them
+    pub fn rects(&self) -> &[(i32, i32, i32, i32)] {
+        &self.rects
+    }
+
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    fn cell(x: i32, y: i32) -> (i32, i32) {
+        (x.div_euclid(CELL), y.div_euclid(CELL))
+    }
+
+    fn id(&self, rect: (i32, i32, i32, i32)) -> Option<usize> {
+        let cell = self.cells.get(&Self::cell(rect.0, rect.1))?;
+        cell.iter().copied().find(|id| self.rects[*id] == rect)
+    }
+
+    // every rect that could possibly touch cc. touching() has the final say
+    fn near(&self, cc: (i32, i32, i32, i32)) -> impl Iterator<Item = usize> + '_ {
+        let (left, top) = Self::cell(cc.0 - self.max_w - 1, cc.1 - self.max_h.max(cc.3) - 1);
+        let (right, bottom) = Self::cell(cc.0 + cc.2 + 1, cc.1 + cc.3 + 1);
+
+        (left..=right)
+            .flat_map(move |x| (top..=bottom).map(move |y| (x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+    }
+
+    // flood fill from initial_coordinates through touching rects, never stepping onto one already claimed in known,
+    // and claiming everything it finds. each rect is only compared with the handful of rects around it
+    pub fn grow(
+        &self,
+        initial_coordinates: &[(i32, i32, i32, i32)],
+        known: &mut [bool],
+    ) -> Vec<(i32, i32, i32, i32)> {
+        let mut found: Vec<(i32, i32, i32, i32)> = Vec::new();
+
+        for i in initial_coordinates.iter() {
+            match self.id(*i) {
+                Some(id) if known[id] => continue,
+                Some(id) => known[id] = true,
+                // not one of ours, so nothing else can stand on it. only has to be checked against found
+                None if found.contains(i) => continue,
+                None => {}
+            }
+
+            found.push(*i);
+        }
+
+        // rects in found from here on haven't been checked for neighbours of their own yet
+        let mut next = 0;
+
+        while let Some(cc) = found.get(next).copied() {
+            next += 1;
+
+            for id in self.near(cc) {
+                if !known[id] && touching(cc, self.rects[id]) {
+                    known[id] = true;
+                    found.push(self.rects[id]);
+                }
+            }
+        }
+
+        trace!(known = found.len(), "character stopped growing");
+
+        found
+    }
+}
+
+// whether i is "connected" to cc, a rect we have already confirmed to belong to the character we are analysing. not
+// symmetric, i can touch cc without cc touching i
+fn touching(cc: (i32, i32, i32, i32), i: (i32, i32, i32, i32)) -> bool {
+    let (cc_x, cc_y, cc_w, cc_h) = (cc.0, cc.1, cc.2, cc.3);
+    let (x, y, w, h) = (i.0, i.1, i.2, i.3);
+
+    // finding adjacent coordinates. basically a bunch of condtions are defined below to find coordinates that are "connected" with each other. as of 7/14/23 this section is kind of a mess and some of the defined conditions may be accidental duplicates. ill clean it up later.
+
+    if x == cc_x && y == cc_y && (w != cc_w || h != cc_h) {
+        return true;
+    }
+
+    // 30 deg E & 40 deg 4. idek visualizing tetris as i make these up.
+    if (x == cc_w + cc_x && y == cc_y - h)
+        || (x == cc_x - w && y == cc_y - cc_h)
+        || (x == cc_x - w && y == cc_y - h)
+    {
+        return true;
+    }
+
+    // ^
+    if cc_y == y - 1 && (cc_x == x + 1 || x == cc_x + 1) {
+        return true;
+    }
+
+    // upside down ^
+    if y + 1 == cc_y && (x + 1 == cc_x || cc_x + 1 == x) {
+        return true;
+    }
+
+    if ((x == cc_x - 1 || x == cc_x + 1) && w == cc_w && h == cc_h && y == cc_y)
+        || ((y == cc_y - 1 || y == cc_y + 1) && w == cc_w && h == cc_h && x == cc_x)
+    {
+        return true;
+    }
+
+    // "A"
+    if (cc_x <= w + x && cc_x >= x) && (y <= cc_y + cc_h && y >= cc_y) {
+        return true;
+    }
+
+    if (y >= cc_y && y <= cc_y + cc_h) && (x >= cc_x && x <= cc_x + cc_w) {
+        return true;
+    }
+
+    // once you know how to analyze all fifteen upright characters from the top, chances are you'll be able to easily analyze the other characters as well regardless of position.
+    (x <= cc_x + cc_w && x >= cc_x - w) && (y >= cc_y - h && y <= cc_y - h + cc_h)
+}
@@ -0,0 +1,469 @@
+// thins a glyph's bitmap down to one cell wide lines and turns those into a graph: the places strokes end or meet, and
+// the strokes running between them. lines and arcs overlap where they join and leave lumps, and counting ends and
+// joins straight off the pixels counts the lumps too. the skeleton doesn't have them
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::bitmap::{GlyphBitmap, SIZE};
+
+// strokes shorter than this, in cells, that stop dead off a junction are thinning's leftovers, not part of the glyph
+const SPUR: f64 = 3.0;
+
+// cells either side of a turning point the direction of a stroke is measured over. any shorter and the steps a slanted
+// line is drawn in look like turns
+const REACH: usize = 3;
+
+// the 8 neighbours of a cell, going round anticlockwise from the one to its right. y grows downwards
+const AROUND: [(i32, i32); 8] = [
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+// zhang-suen: peel away border cells a side at a time until nothing more can go without breaking a line
+pub fn thin(bitmap: &GlyphBitmap) -> GlyphBitmap {
+    let mut thinned = *bitmap;
+
+    loop {
+        let mut changed = false;
+
+        for pass in 0..2 {
+            let doomed: Vec<(i32, i32)> = thinned
+                .cells()
+                .filter(|(x, y)| {
+                    let p = ring(&thinned, *x, *y);
+                    let (east, north, west, south) = (p[0], p[2], p[4], p[6]);
+
+                    let neighbours = p.iter().filter(|i| **i).count();
+                    (2..=6).contains(&neighbours)
+                        && transitions(&p) == 1
+                        // the first pass takes cells off the bottom and right edges and the corner at the top left,
+                        // the second the other way round
+                        && if pass == 0 {
+                            !(east && south && (north || west))
+                        } else {
+                            !(north && west && (east || south))
+                        }
+                })
+                .collect();
+
+            changed |= !doomed.is_empty();
+
+            for (x, y) in doomed {
+                thinned.clear(x, y);
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    // zhang-suen leaves steps where a line goes diagonally, and the middle cell of a step looks like it has three
+    // neighbours. take those out one at a time while they still don't hold anything together
+    while let Some((x, y)) = step(&thinned) {
+        thinned.clear(x, y);
+    }
+
+    thinned
+}
+
+// a cell with neighbours that would all still touch without it, that isn't the end of a line
+fn step(bitmap: &GlyphBitmap) -> Option<(i32, i32)> {
+    bitmap.cells().find(|(x, y)| {
+        let p = ring(bitmap, *x, *y);
+        p.iter().filter(|i| **i).count() >= 2 && connectivity(&p) == 1 && !is_end(&p)
+    })
+}
+
+// fills cells with ink on opposite sides of them, where turning a one pixel line upright dropped a pixel out of it
+fn bridge(bitmap: &GlyphBitmap) -> GlyphBitmap {
+    let mut bridged = *bitmap;
+
+    for y in 0..SIZE as i32 {
+        for x in 0..SIZE as i32 {
+            let p = ring(bitmap, x, y);
+            if (0..4).any(|i| p[i] && p[i + 4]) {
+                bridged.set(x, y);
+            }
+        }
+    }
+
+    bridged
+}
+
+// whether each neighbour is set, in AROUND's order
+fn ring(bitmap: &GlyphBitmap, x: i32, y: i32) -> [bool; 8] {
+    AROUND.map(|(dx, dy)| bitmap.get(x + dx, y + dy))
+}
+
+// how many times going once round the neighbours steps from unset to set
+fn transitions(p: &[bool; 8]) -> usize {
+    (0..8).filter(|i| !p[*i] && p[(i + 1) % 8]).count()
+}
+
+// yokoi's connectivity number for 8-connected lines: 1 means taking the cell away doesn't split its neighbours up, more
+// than that that it's holding a line together
+fn connectivity(p: &[bool; 8]) -> usize {
+    let unset = |i: usize| !p[i % 8];
+
+    [0, 2, 4, 6]
+        .iter()
+        .filter(|k| unset(**k) && !(unset(**k + 1) && unset(**k + 2)))
+        .count()
+}
+
+// two neighbours right next to each other are still only one way out
+fn is_end(p: &[bool; 8]) -> bool {
+    let neighbours: Vec<usize> = (0..8).filter(|i| p[*i]).collect();
+
+    match neighbours[..] {
+        [_] => true,
+        [a, b] => b - a == 1 || (a == 0 && b == 7),
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum NodeKind {
+    // a stroke stops here
+    End,
+    // three or more strokes meet here
+    Junction,
+    // a stroke that closes on itself without ending or meeting anything, like an O. somewhere has to be its node
+    Loop,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Node {
+    pub kind: NodeKind,
+    // cells of the skeleton the node is made of. a junction can take a few cells where lines meet at shallow angles
+    pub cells: Vec<(i32, i32)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Edge {
+    // indexes into the graph's nodes. the same for both ends of a loop
+    pub from: usize,
+    pub to: usize,
+    // the cells between the two nodes, in order
+    pub cells: Vec<(i32, i32)>,
+    // in cells, counting diagonal steps as longer
+    pub length: f64,
+    // roughly 1 / the radius of the circle it follows, in cells. 0 for a straight line
+    pub curvature: f64,
+    // the share of its cells drawn by an arc rather than a line, once mark_arcs has been told which those are
+    pub arc: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StrokeGraph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl StrokeGraph {
+    // closes the gaps turning the glyph upright left, thins it, and follows every stroke of what's left. thinning
+    // leaves little spurs off corners where the ink was thick, and those get cut off before anyone counts the ends
+    pub fn of(bitmap: &GlyphBitmap) -> StrokeGraph {
+        let mut skeleton = thin(&bridge(bitmap));
+
+        // dots on their own, unless that's all there is
+        let dots: Vec<(i32, i32)> = skeleton
+            .cells()
+            .filter(|(x, y)| !ring(&skeleton, *x, *y).contains(&true))
+            .collect();
+        if dots.len() < skeleton.cells().count() {
+            for (x, y) in dots {
+                skeleton.clear(x, y);
+            }
+        }
+
+        loop {
+            let graph = StrokeGraph::trace(&skeleton);
+
+            let spurs: Vec<&Edge> = graph.edges.iter().filter(|i| graph.is_spur(i)).collect();
+            if spurs.is_empty() {
+                return graph;
+            }
+
+            for spur in spurs {
+                // the cell at the junction stays, or the strokes that meet there come apart
+                let junction = &graph.nodes[if graph.nodes[spur.from].kind == NodeKind::End {
+                    spur.to
+                } else {
+                    spur.from
+                }];
+
+                for (x, y) in spur.cells.iter().filter(|i| !junction.cells.contains(i)) {
+                    skeleton.clear(*x, *y);
+                }
+            }
+
+            skeleton = thin(&skeleton);
+        }
+    }
+
+    // a short stroke off a junction to an end, or a speck on its own
+    fn is_spur(&self, edge: &Edge) -> bool {
+        let ends = [edge.from, edge.to]
+            .iter()
+            .filter(|i| self.nodes[**i].kind == NodeKind::End)
+            .count();
+        let alone = edge.from != edge.to && ends == 2 && self.edges.len() > 1;
+
+        edge.length < SPUR && ((ends == 1 && edge.from != edge.to) || alone)
+    }
+
+    // skeleton has to be thinned already
+    pub fn trace(skeleton: &GlyphBitmap) -> StrokeGraph {
+        let mut graph = StrokeGraph::default();
+
+        // which node every node cell belongs to. once the steps are gone a cell on a line has two neighbours, so any more
+        // is lines meeting. junction cells that touch are the same junction
+        let mut owner: HashMap<(i32, i32), usize> = HashMap::new();
+
+        for (x, y) in skeleton.cells() {
+            let p = ring(skeleton, x, y);
+
+            let kind = if is_end(&p) {
+                NodeKind::End
+            } else if p.iter().filter(|i| **i).count() >= 3 {
+                NodeKind::Junction
+            } else {
+                continue;
+            };
+
+            let joins = AROUND
+                .iter()
+                .filter_map(|(dx, dy)| owner.get(&(x + dx, y + dy)))
+                .copied()
+                .find(|i| kind == NodeKind::Junction && graph.nodes[*i].kind == NodeKind::Junction);
+
+            let node = joins.unwrap_or_else(|| {
+                graph.nodes.push(Node {
+                    kind,
+                    cells: Vec::new(),
+                });
+                graph.nodes.len() - 1
+            });
+
+            graph.nodes[node].cells.push((x, y));
+            owner.insert((x, y), node);
+        }
+
+        let mut walked: HashSet<(i32, i32)> = HashSet::new();
+
+        for node in 0..graph.nodes.len() {
+            for start in graph.nodes[node].cells.clone() {
+                for (dx, dy) in AROUND {
+                    let next = (start.0 + dx, start.1 + dy);
+
+                    if !skeleton.get(next.0, next.1)
+                        || walked.contains(&next)
+                        || owner.get(&next) == Some(&node)
+                    {
+                        continue;
+                    }
+
+                    // two nodes right next to each other. only join them once
+                    if let Some(other) = owner.get(&next) {
+                        if *other > node {
+                            graph.edges.push(Edge::new(node, *other, vec![start, next]));
+                        }
+                        continue;
+                    }
+
+                    let (to, path) = walk(skeleton, &owner, start, next, &mut walked);
+                    let to = to.unwrap_or_else(|| graph.dead_end(&mut owner, &path));
+                    graph.edges.push(Edge::new(node, to, path));
+                }
+            }
+        }
+
+        // whatever's left has no ends or junctions on it, so it goes round in a circle
+        while let Some(start) = skeleton
+            .cells()
+            .find(|i| !walked.contains(i) && !owner.contains_key(i))
+        {
+            graph.nodes.push(Node {
+                kind: NodeKind::Loop,
+                cells: vec![start],
+            });
+            let node = graph.nodes.len() - 1;
+            owner.insert(start, node);
+
+            let Some(next) = AROUND
+                .iter()
+                .map(|(dx, dy)| (start.0 + dx, start.1 + dy))
+                .find(|i| skeleton.get(i.0, i.1) && !walked.contains(i))
+            else {
+                // a dot on its own
+                graph.nodes[node].kind = NodeKind::End;
+                continue;
+            };
+
+            let (_, path) = walk(skeleton, &owner, start, next, &mut walked);
+            graph.edges.push(Edge::new(node, node, path));
+        }
+
+        graph
+    }
+
+    // where a walk ran out without getting to a node, which is an end as far as anyone reading the graph cares
+    fn dead_end(&mut self, owner: &mut HashMap<(i32, i32), usize>, path: &[(i32, i32)]) -> usize {
+        let last = path[path.len() - 1];
+        self.nodes.push(Node {
+            kind: NodeKind::End,
+            cells: vec![last],
+        });
+        owner.insert(last, self.nodes.len() - 1);
+
+        self.nodes.len() - 1
+    }
+
+    // works out each edge's arc share from a bitmap of the glyph's arc pixels, laid out like the one the graph came
+    // from. thinning moves lines into the middle of the ink, so a cell next to an arc pixel counts too
+    pub fn mark_arcs(&mut self, arcs: &GlyphBitmap) {
+        let near = |(x, y): (i32, i32)| {
+            arcs.get(x, y) || AROUND.iter().any(|(dx, dy)| arcs.get(x + dx, y + dy))
+        };
+
+        for edge in self.edges.iter_mut() {
+            edge.arc =
+                edge.cells.iter().filter(|i| near(**i)).count() as f64 / edge.cells.len() as f64;
+        }
+    }
+
+    // every cell of every node and stroke, which is the skeleton the graph was traced from with its spurs cut off
+    pub fn skeleton(&self) -> GlyphBitmap {
+        let mut skeleton = GlyphBitmap::default();
+
+        for (x, y) in self
+            .nodes
+            .iter()
+            .flat_map(|i| i.cells.iter())
+            .chain(self.edges.iter().flat_map(|i| i.cells.iter()))
+        {
+            skeleton.set(*x, *y);
+        }
+
+        skeleton
+    }
+
+    pub fn count(&self, kind: NodeKind) -> usize {
+        self.nodes.iter().filter(|i| i.kind == kind).count()
+    }
+
+    // how bent the most bent stroke is
+    pub fn max_curvature(&self) -> f64 {
+        self.edges.iter().map(|i| i.curvature).fold(0.0, f64::max)
+    }
+}
+
+// follows a stroke from start through next until it gets to a node. returns the node it got to, or None if the stroke
+// ran out somewhere thinning left no end, and every cell on the way, both ends included
+fn walk(
+    skeleton: &GlyphBitmap,
+    owner: &HashMap<(i32, i32), usize>,
+    start: (i32, i32),
+    next: (i32, i32),
+    walked: &mut HashSet<(i32, i32)>,
+) -> (Option<usize>, Vec<(i32, i32)>) {
+    let mut path = vec![start, next];
+    let (mut previous, mut current) = (start, next);
+
+    loop {
+        if let Some(node) = owner.get(&current) {
+            return (Some(*node), path);
+        }
+
+        walked.insert(current);
+
+        // not straight back to the node it set off from
+        let leaving = path.len() <= 3;
+        let neighbours: Vec<(i32, i32)> = AROUND
+            .iter()
+            .map(|(dx, dy)| (current.0 + dx, current.1 + dy))
+            .filter(|i| skeleton.get(i.0, i.1) && *i != previous)
+            .filter(|i| !(leaving && owner.contains_key(i) && owner.get(i) == owner.get(&start)))
+            .collect();
+
+        // a node wins over carrying on, so strokes stop at the junction they run into rather than going round it
+        let Some(following) = neighbours
+            .iter()
+            .find(|i| owner.contains_key(i))
+            .or_else(|| neighbours.iter().find(|i| !walked.contains(i)))
+            .copied()
+        else {
+            return (None, path);
+        };
+
+        path.push(following);
+        (previous, current) = (current, following);
+    }
+}
+
+impl Edge {
+    fn new(from: usize, to: usize, cells: Vec<(i32, i32)>) -> Edge {
+        let length: f64 = cells
+            .windows(2)
+            .map(|i| f64::from(i[1].0 - i[0].0).hypot(f64::from(i[1].1 - i[0].1)))
+            .sum();
+
+        Edge {
+            from,
+            to,
+            curvature: curvature(&cells),
+            arc: 0.0,
+            cells,
+            length,
+        }
+    }
+}
+
+// how fast the stroke turns, in radians per cell, going by the middle of how much it turns along its length rather
+// than the most. a line with a corner in it turns hard in one place and not at all everywhere else, where an arc turns
+// the same amount all the way round. a stroke too short to tell is taken to be straight
+fn curvature(cells: &[(i32, i32)]) -> f64 {
+    let closed = cells.len() > 2 && cells.first() == cells.last();
+    let cells = if closed { &cells[1..] } else { cells };
+    let n = cells.len();
+
+    if n < 2 * REACH + 1 {
+        return 0.0;
+    }
+
+    let at = |i: usize| if closed { cells[i % n] } else { cells[i] };
+    let direction = |a: (i32, i32), b: (i32, i32)| {
+        let (dx, dy) = (f64::from(b.0 - a.0), f64::from(b.1 - a.1));
+        (dy.atan2(dx), dx.hypot(dy))
+    };
+
+    let positions = if closed {
+        REACH..n + REACH
+    } else {
+        REACH..n - REACH
+    };
+    let mut turns: Vec<f64> = positions
+        .map(|i| {
+            let (before, a) = direction(at(i - REACH), at(i));
+            let (after, b) = direction(at(i), at(i + REACH));
+            let turn = (after - before + std::f64::consts::PI)
+                .rem_euclid(2.0 * std::f64::consts::PI)
+                - std::f64::consts::PI;
+
+            turn.abs() / ((a + b) / 2.0)
+        })
+        .collect();
+
+    turns.sort_by(f64::total_cmp);
+    turns[turns.len() / 2]
+}
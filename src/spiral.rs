@@ -0,0 +1,232 @@
+// the characters aren't on one circle. every character is turned 10 degrees further than the one before, so 253 of
+// them go round about seven times, and each time round they sit a bit closer to the centre. that's an archimedean
+// spiral: the radius shrinks by the same amount every turn. many characters share a polar angle and only the radius
+// says which turn they're on, so ordering goes by where a character sits along the spiral, not by its angle
+
+use serde::Serialize;
+
+// radius = start_radius + pitch * turns, where turns counts from straight up, anticlockwise, like the page draws them
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Spiral {
+    pub centre: (f64, f64),
+    // distance from the centre at 0 degrees on the first turn
+    pub start_radius: f64,
+    // how much the radius changes every full turn. negative when the spiral winds inwards
+    pub pitch: f64,
+}
+
+// where one character sits on a spiral
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Placement {
+    // 0 for the first time round
+    pub turn: i32,
+    // degrees anticlockwise from straight up, 0 to 360
+    pub angle: f64,
+    // degrees along the spiral from its start, turn * 360 + angle
+    pub along: f64,
+    // how much further from the centre the character is than the spiral says it should be
+    pub residual: f64,
+}
+
+// below this the spiral is as good as a circle and the radius can't tell turns apart
+const MIN_PITCH: f64 = 1.0;
+
+impl Spiral {
+    // least squares fit through points, where along[i] is how far along the spiral points[i] is thought to be. with
+    // those known, x = cx - r sin(along) and y = cy - r cos(along) are linear in the centre, start radius and pitch
+    pub fn fit(points: &[(f64, f64)], along: &[f64]) -> Option<Spiral> {
+        if points.len() < 3 || points.len() != along.len() {
+            return None;
+        }
+
+        // normal equations for the unknowns (cx, cy, start radius, pitch)
+        let mut ata = [[0.0; 4]; 4];
+        let mut atb = [0.0; 4];
+
+        for (point, along) in points.iter().zip(along.iter()) {
+            let (sin, cos) = along.to_radians().sin_cos();
+            let turns = along / 360.0;
+
+            for (row, value) in [
+                ([1.0, 0.0, -sin, -turns * sin], point.0),
+                ([0.0, 1.0, -cos, -turns * cos], point.1),
+            ] {
+                for i in 0..4 {
+                    for j in 0..4 {
+                        ata[i][j] += row[i] * row[j];
+                    }
+                    atb[i] += row[i] * value;
+                }
+            }
+        }
+
+        let [cx, cy, start_radius, pitch] = solve(ata, atb)?;
+
+        Some(Spiral {
+            centre: (cx, cy),
+            start_radius,
+            pitch,
+        })
+    }
+
+    // the same fit again and again, each time going by where the last fit put the points, until no point changes
+    // turn. guess is where each point is thought to be to begin with
+    pub fn refine(points: &[(f64, f64)], guess: &[f64]) -> Option<(Spiral, Vec<Placement>)> {
+        let mut along = guess.to_vec();
+        let mut fitted = None;
+
+        for _ in 0..8 {
+            let spiral = Spiral::fit(points, &along)?;
+            let placements: Vec<Placement> = points
+                .iter()
+                .zip(along.iter())
+                .map(|(point, along)| spiral.place(*point, *along))
+                .collect();
+
+            let settled = placements
+                .iter()
+                .zip(along.iter())
+                .all(|(i, along)| (i.along - along).abs() < 180.0);
+            along = placements.iter().map(|i| i.along).collect();
+            fitted = Some((spiral, placements));
+
+            if settled {
+                break;
+            }
+        }
+
+        fitted
+    }
+
+    pub fn radius_at(&self, along: f64) -> f64 {
+        self.start_radius + self.pitch * along / 360.0
+    }
+
+    // degrees anticlockwise from straight up, 0 to 360. y grows downwards on the page
+    pub fn angle_of(&self, point: (f64, f64)) -> f64 {
+        let dx = point.0 - self.centre.0;
+        let dy = self.centre.1 - point.1;
+
+        (-dx).atan2(dy).to_degrees().rem_euclid(360.0)
+    }
+
+    // puts point on the turn its radius says it's on. near is where it's thought to be, and only decides the turn
+    // when the spiral is too close to a circle for the radius to
+    pub fn place(&self, point: (f64, f64), near: f64) -> Placement {
+        let angle = self.angle_of(point);
+        let radius = ((point.0 - self.centre.0).powi(2) + (point.1 - self.centre.1).powi(2)).sqrt();
+
+        let turns = if self.pitch.abs() < MIN_PITCH {
+            near / 360.0
+        } else {
+            (radius - self.start_radius) / self.pitch
+        };
+        let turn = (turns - angle / 360.0).round() as i32;
+        let along = f64::from(turn) * 360.0 + angle;
+
+        Placement {
+            turn,
+            angle,
+            along,
+            residual: radius - self.radius_at(along),
+        }
+    }
+}
+
+// gaussian elimination with partial pivoting. None when the system is singular
+fn solve(mut a: [[f64; 4]; 4], mut b: [f64; 4]) -> Option<[f64; 4]> {
+    for col in 0..4 {
+        let pivot = (col..4).max_by(|i, j| a[*i][col].abs().total_cmp(&a[*j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-9 {
+            return None;
+        }
+
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        let pivot_row = a[col];
+
+        for row in col + 1..4 {
+            let factor = a[row][col] / pivot_row[col];
+            for (value, pivot) in a[row].iter_mut().zip(pivot_row.iter()).skip(col) {
+                *value -= factor * pivot;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = [0.0; 4];
+    for row in (0..4).rev() {
+        let rest: f64 = (row + 1..4).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - rest) / a[row][row];
+    }
+
+    Some(x)
+}
+
+// a character more than this much of the pitch off the spiral is getting close to looking like it's on the next turn
+const OFF_CURVE: f64 = 0.25;
+// characters are 10 degrees apart along the spiral. closer or further than these and one is in the wrong place
+const SHARED_SLOT: f64 = 5.0;
+const GAP: f64 = 15.0;
+
+// a spiral fitted through the characters, and where each of them sits on it
+#[derive(Debug, Clone, Serialize)]
+pub struct SpiralLayout {
+    pub spiral: Spiral,
+    pub misfits: Vec<Misfit>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum MisfitKind {
+    // too far from the spiral to be sure which turn it's on
+    OffCurve,
+    // practically on top of the character before it
+    SharedSlot,
+    // far enough on from the character before it that one seems to be missing in between
+    AfterGap,
+}
+
+// a character the spiral doesn't account for
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Misfit {
+    // its place in the reading order
+    pub glyph: i32,
+    pub placement: Placement,
+    pub kind: MisfitKind,
+}
+
+impl SpiralLayout {
+    // placements have to be in order along the spiral already, placements[i] being character i
+    pub fn new(spiral: Spiral, placements: &[Placement]) -> SpiralLayout {
+        let mut misfits = Vec::new();
+
+        for (glyph, placement) in (0..).zip(placements.iter()) {
+            let mut misfit = |kind| {
+                misfits.push(Misfit {
+                    glyph,
+                    placement: *placement,
+                    kind,
+                })
+            };
+
+            if placement.residual.abs() > spiral.pitch.abs() * OFF_CURVE
+                && spiral.pitch.abs() >= MIN_PITCH
+            {
+                misfit(MisfitKind::OffCurve);
+            }
+
+            if glyph > 0 {
+                let step = placement.along - placements[glyph as usize - 1].along;
+
+                if step < SHARED_SLOT {
+                    misfit(MisfitKind::SharedSlot);
+                } else if step > GAP {
+                    misfit(MisfitKind::AfterGap);
+                }
+            }
+        }
+
+        SpiralLayout { spiral, misfits }
+    }
+}
@@ -0,0 +1,194 @@
+// vector version of the debug render. the draw commands come out as real arcs and lines instead of divs, and every
+// character is boxed and labelled with what the decoder made of it. the output is plain text with a fixed layout so
+// two runs can be diffed in code review
+
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{
+    draw::{bounds, DrawCommand, Rect},
+    glyph::Glyph,
+    image::palette,
+};
+
+const MARGIN: f64 = 20.0;
+
+pub fn render(commands: &[DrawCommand], glyphs: &[Glyph]) -> String {
+    let mut out = String::new();
+
+    let all_rects: Vec<Rect> = commands
+        .iter()
+        .flat_map(|i| i.rasterize().unwrap_or_default())
+        .collect();
+    let (left, top, right, bottom) = bounds(&all_rects).unwrap_or((0, 0, 1, 1));
+
+    let (min_x, min_y) = (f64::from(left) - MARGIN, f64::from(top) - MARGIN);
+    let (width, height) = (
+        f64::from(right - left) + 2.0 * MARGIN,
+        f64::from(bottom - top) + 2.0 * MARGIN,
+    );
+
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{:.0} {:.0} {:.0} {:.0}" width="{:.0}" height="{:.0}">"#,
+        min_x, min_y, width, height, width * 2.0, height * 2.0
+    )
+    .unwrap();
+
+    out.push_str(concat!(
+        "<defs>\n",
+        r#"<marker id="arrow" viewBox="0 0 6 6" refX="5" refY="3" markerWidth="4" markerHeight="4" orient="auto">"#,
+        r#"<path d="M0,0 L6,3 L0,6 z" fill="white"/></marker>"#,
+        "\n</defs>\n",
+        "<style>text { font: 4px monospace; fill: white; } .bbox { fill: none; stroke: #888; stroke-width: 0.3; } ",
+        ".order { stroke: white; stroke-width: 0.3; stroke-opacity: 0.5; marker-end: url(#arrow); } ",
+        ".stroke { fill: none; stroke-width: 1; stroke-linecap: round; }</style>\n",
+    ));
+
+    writeln!(
+        out,
+        r#"<rect x="{:.0}" y="{:.0}" width="{:.0}" height="{:.0}" fill="black"/>"#,
+        min_x, min_y, width, height
+    )
+    .unwrap();
+
+    // which character each rect ended up in, so the commands can be coloured to match
+    let owner: HashMap<&Rect, i32> = glyphs
+        .iter()
+        .flat_map(|g| g.rects.iter().map(move |r| (r, g.index)))
+        .collect();
+
+    out.push_str("<g id=\"commands\">\n");
+
+    for (i, command) in commands.iter().enumerate() {
+        let colour = command
+            .rasterize()
+            .unwrap_or_default()
+            .first()
+            .and_then(|r| owner.get(r))
+            .map(|index| hex(palette(*index)))
+            .unwrap_or_else(|| String::from("#5a5a5a"));
+
+        writeln!(
+            out,
+            r#"<path class="stroke" stroke="{}" d="{}"><title>command {}: {:?}</title></path>"#,
+            colour,
+            path_data(command),
+            i,
+            command
+        )
+        .unwrap();
+    }
+
+    out.push_str("</g>\n<g id=\"glyphs\">\n");
+
+    for glyph in glyphs.iter() {
+        let (l, t, r, b) = match bounds(&glyph.rects) {
+            Some(bounds) => bounds,
+            None => continue,
+        };
+
+        let (id, confidence) = match &glyph.recognition {
+            Some(recognition) => (
+                format!("{:?}", recognition.character.id),
+                recognition.confidence,
+            ),
+            None => (String::from("?"), 0.0),
+        };
+
+        // the angle the reading order gave next to the one it sits at, so a glyph the order put in the wrong place
+        // shows up as the two not matching
+        let measured = match glyph.measured_angle() {
+            Some(angle) => format!("{:.1}", angle),
+            None => String::from("?"),
+        };
+
+        writeln!(
+            out,
+            r#"<g id="glyph-{}"><title>index {} angle {} measured {} section {:?} {} ({:.2})</title>"#,
+            glyph.index, glyph.index, glyph.angle, measured, glyph.section, id, confidence
+        )
+        .unwrap();
+
+        writeln!(
+            out,
+            r#"<rect class="bbox" x="{}" y="{}" width="{}" height="{}"/>"#,
+            l,
+            t,
+            r - l,
+            b - t
+        )
+        .unwrap();
+
+        writeln!(
+            out,
+            r#"<text x="{}" y="{}">{} {}°/{}° {:?} {} {:.2}</text>"#,
+            l,
+            t - 1,
+            glyph.index,
+            glyph.angle,
+            measured,
+            glyph.section,
+            id,
+            confidence
+        )
+        .unwrap();
+
+        out.push_str("</g>\n");
+    }
+
+    out.push_str("</g>\n<g id=\"reading-order\">\n");
+
+    let centres: Vec<(f64, f64)> = glyphs
+        .iter()
+        .filter(|g| !g.rects.is_empty())
+        .map(|g| g.centroid)
+        .collect();
+
+    for pair in centres.windows(2) {
+        writeln!(
+            out,
+            r#"<line class="order" x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}"/>"#,
+            pair[0].0, pair[0].1, pair[1].0, pair[1].1
+        )
+        .unwrap();
+    }
+
+    out.push_str("</g>\n</svg>\n");
+
+    out
+}
+
+// drawArc goes anticlockwise on screen (y grows downwards and it subtracts the sine), which is sweep flag 0 in svg.
+// a full circle can't be one svg arc so anything over 180 degrees is split in two
+fn path_data(command: &DrawCommand) -> String {
+    match *command {
+        DrawCommand::Line { x1, y1, x2, y2 } => format!("M{},{} L{},{}", x1, y1, x2, y2),
+        DrawCommand::Arc { x, y, r, s, e } => {
+            let (x, y, r) = (f64::from(x), f64::from(y), f64::from(r));
+            let point = |deg: f64| {
+                let rad = deg.to_radians();
+                (x + r * rad.cos(), y - r * rad.sin())
+            };
+
+            let start = f64::from(s);
+            let sweep = f64::from(e).min(360.0);
+            let (sx, sy) = point(start);
+
+            let mut d = format!("M{:.2},{:.2}", sx, sy);
+
+            let mut done = 0.0;
+            while done < sweep {
+                let step = (sweep - done).min(180.0);
+                let (ex, ey) = point(start + done + step);
+                write!(d, " A{},{} 0 0 0 {:.2},{:.2}", r, r, ex, ey).unwrap();
+                done += step;
+            }
+
+            d
+        }
+    }
+}
+
+fn hex(colour: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", colour[0], colour[1], colour[2])
+}
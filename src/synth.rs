@@ -0,0 +1,227 @@
+// made up mission pages. the real page can only be fetched while logged in and changes every time, so benchmarks and
+// tests draw their own: random characters on an inward spiral, each one turned 10 degrees further than the last, the
+// same way the mission lays them out. the strokes are rough but every character uses the same mix of arcs and lines
+// the real ones do
+
+use std::f64::consts::PI;
+
+use crate::{
+    draw::DrawCommand,
+    fingerprint::KNOWN_GOOD_SCRIPT,
+    transform::{Affine, Shape},
+};
+
+// every character the mission draws
+pub const ALPHABET: &[u8; 16] = b"0123456789ABCDEF";
+
+// the values drawData would hold for a page, and the characters they spell
+#[derive(Debug, Clone)]
+pub struct SyntheticPage {
+    pub text: String,
+    pub draw_data: Vec<i32>,
+    // where every character in text was drawn around
+    pub places: Vec<(f64, f64)>,
+}
+
+impl SyntheticPage {
+    // a whole page, with draw_data dropped into the known good script
+    pub fn html(&self) -> String {
+        let numbers: Vec<String> = self.draw_data.iter().map(|i| i.to_string()).collect();
+        let script = KNOWN_GOOD_SCRIPT.replace(
+            "new Array(...)",
+            &format!("new Array({})", numbers.join(",")),
+        );
+
+        format!(
+            "<html><head><script type=\"text/javascript\">\n{}</script></head><body></body></html>\n",
+            script
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Layout {
+    pub glyphs: usize,
+    // distance of the first character from the centre
+    pub outer_radius: f64,
+    // how much closer to the centre every character is than the one before
+    pub pitch: f64,
+}
+
+impl Layout {
+    // about what the mission serves. the quadrant walk picks the next character by which rect is furthest along in
+    // its quarter, so the turns have to be far enough apart that no character on the next turn in can beat the one
+    // it's after, even where a quarter ends and the two sit in line. 40 pixels a turn with the innermost turn still
+    // well clear of the centre segments every seed
+    pub const REALISTIC: Layout = Layout {
+        glyphs: 253,
+        outer_radius: 600.0,
+        pitch: 40.0 / 36.0,
+    };
+
+    // twice the characters, on a ring a little wider so the last of them stay clear of the centre. segmentation still
+    // only reads the first 253, but every search it does runs over all of them
+    pub const LARGE: Layout = Layout {
+        glyphs: 506,
+        outer_radius: 700.0,
+        pitch: 40.0 / 36.0,
+    };
+}
+
+// one stroke of a character drawn upright around (0, 0), y pointing down like on the page. growing a character only
+// steps from a rect to the ones touching() says it touches, which isn't symmetric, so a stroke that meets another part
+// way along is split there. that way every character is in one piece whichever of its rects growing starts from
+#[derive(Debug, Clone, Copy)]
+enum Stroke {
+    Line(f64, f64, f64, f64),
+    // centre x, centre y, radius, start angle, sweep
+    Arc(f64, f64, i32, i32, i32),
+}
+
+use Stroke::{Arc, Line};
+
+impl From<Stroke> for Shape {
+    fn from(stroke: Stroke) -> Shape {
+        match stroke {
+            Line(x1, y1, x2, y2) => Shape::Line {
+                from: (x1, y1),
+                to: (x2, y2),
+            },
+            Arc(x, y, r, s, e) => Shape::Arc {
+                centre: (x, y),
+                radius: f64::from(r),
+                start: f64::from(s),
+                sweep: f64::from(e),
+            },
+        }
+    }
+}
+
+fn strokes(c: u8) -> &'static [Stroke] {
+    match c {
+        b'0' => &[
+            Arc(0.0, -2.0, 4, 0, 180),
+            Arc(0.0, 2.0, 4, 180, 180),
+            Line(-4.0, -2.0, -4.0, 2.0),
+            Line(4.0, -2.0, 4.0, 2.0),
+        ],
+        b'1' => &[
+            Line(0.0, -6.0, 0.0, 6.0),
+            Line(-2.0, -4.0, 0.0, -6.0),
+            Line(0.0, 6.0, -3.0, 6.0),
+            Line(0.0, 6.0, 3.0, 6.0),
+        ],
+        b'2' => &[
+            Arc(0.0, -3.0, 3, -30, 210),
+            Line(3.0, -2.0, -4.0, 6.0),
+            Line(-4.0, 6.0, 4.0, 6.0),
+        ],
+        b'3' => &[Arc(0.0, -3.0, 3, -90, 270), Arc(0.0, 3.0, 3, 180, 270)],
+        b'4' => &[
+            Line(2.0, 6.0, 2.0, -6.0),
+            Line(2.0, -6.0, -4.0, 2.0),
+            Line(-4.0, 2.0, 4.0, 2.0),
+        ],
+        b'5' => &[
+            Line(3.0, -6.0, -3.0, -6.0),
+            Line(-3.0, -6.0, -3.0, -3.0),
+            Line(-3.0, -3.0, -3.0, -1.0),
+            Arc(0.0, 2.0, 4, 220, 270),
+        ],
+        b'6' => &[Arc(0.0, 2.0, 4, 0, 360), Line(-4.0, 2.0, 2.0, -6.0)],
+        b'7' => &[Line(-4.0, -6.0, 4.0, -6.0), Line(4.0, -6.0, -1.0, 6.0)],
+        b'8' => &[Arc(0.0, -3.0, 3, 0, 360), Arc(0.0, 3.0, 3, 0, 360)],
+        b'9' => &[Arc(0.0, -2.0, 4, 0, 360), Line(4.0, -2.0, -2.0, 6.0)],
+        b'A' => &[
+            Line(-4.0, 6.0, 0.0, -6.0),
+            Line(0.0, -6.0, 4.0, 6.0),
+            Line(-2.0, 1.0, 2.0, 1.0),
+        ],
+        b'B' => &[
+            Line(-4.0, -6.0, -4.0, 6.0),
+            Line(-4.0, -6.0, 0.0, -6.0),
+            Line(-4.0, 0.0, 0.0, 0.0),
+            Line(-4.0, 6.0, 0.0, 6.0),
+            Arc(0.0, -3.0, 3, -90, 180),
+            Arc(0.0, 3.0, 3, -90, 180),
+        ],
+        b'C' => &[Arc(0.0, 0.0, 6, 45, 270)],
+        b'D' => &[
+            Line(-4.0, -6.0, -4.0, 6.0),
+            Line(-4.0, -6.0, -2.0, -6.0),
+            Line(-4.0, 6.0, -2.0, 6.0),
+            Arc(-2.0, 0.0, 6, -90, 180),
+        ],
+        b'E' => &[
+            Line(-4.0, -6.0, -4.0, 0.0),
+            Line(-4.0, 0.0, -4.0, 6.0),
+            Line(-4.0, -6.0, 4.0, -6.0),
+            Line(-4.0, 0.0, 2.0, 0.0),
+            Line(-4.0, 6.0, 4.0, 6.0),
+        ],
+        _ => &[
+            Line(-4.0, -6.0, -4.0, 0.0),
+            Line(-4.0, 0.0, -4.0, 6.0),
+            Line(-4.0, -6.0, 4.0, -6.0),
+            Line(-4.0, 0.0, 2.0, 0.0),
+        ],
+    }
+}
+
+// the same seed and layout always give the same page
+pub fn generate(seed: u64, layout: Layout) -> SyntheticPage {
+    let mut state = seed;
+    let text: String = (0..layout.glyphs)
+        .map(|_| ALPHABET[(splitmix(&mut state) % 16) as usize] as char)
+        .collect();
+
+    // far enough in that every x stays above 10, so no line is ever mistaken for an arc by the page's loop
+    let centre = layout.outer_radius + 50.0;
+    let mut draw_data = Vec::new();
+    let mut places = Vec::new();
+
+    for (i, c) in text.bytes().enumerate() {
+        let turn = 10 * i as i32;
+        let theta = f64::from(turn) * PI / 180.0;
+        let radius = layout.outer_radius - layout.pitch * i as f64;
+
+        let at = (centre - radius * theta.sin(), centre - radius * theta.cos());
+        places.push(at);
+
+        for command in character(c, turn, at) {
+            match command {
+                DrawCommand::Line { x1, y1, x2, y2 } => {
+                    draw_data.extend_from_slice(&[x1, y1, x2, y2])
+                }
+                DrawCommand::Arc { x, y, r, s, e } => draw_data.extend_from_slice(&[x, y, r, s, e]),
+            }
+        }
+    }
+
+    SyntheticPage {
+        text,
+        draw_data,
+        places,
+    }
+}
+
+// the commands that draw c centred on at, turned anticlockwise by turn degrees
+pub fn character(c: u8, turn: i32, at: (f64, f64)) -> Vec<DrawCommand> {
+    let place = Affine::rotation(f64::from(turn)).then(&Affine::translation(at.0, at.1));
+
+    strokes(c)
+        .iter()
+        .filter_map(|i| place.apply_shape(&Shape::from(*i)))
+        .map(|i| i.to_command())
+        .collect()
+}
+
+// small, fast and good enough for picking characters
+pub(crate) fn splitmix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,172 @@
+// the mission only accepts answers for a few seconds after the page is served, so every stage is timed against one
+// budget for the whole run. a stage that finishes past the deadline fails the run instead of letting a late answer
+// get submitted
+
+use std::{
+    error::Error,
+    fmt::{self, Write},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use tracing::{debug, info_span};
+
+use crate::HTSError;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct StageTiming {
+    pub stage: &'static str,
+    pub millis: f64,
+}
+
+impl StageTiming {
+    pub fn new(stage: &'static str, elapsed: Duration) -> StageTiming {
+        StageTiming {
+            stage,
+            millis: elapsed.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+// stage is the one that was running when the budget ran out, elapsed is measured from the start of the run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeadlineExceeded {
+    pub stage: &'static str,
+    pub elapsed: Duration,
+    pub budget: Duration,
+}
+
+impl fmt::Display for DeadlineExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} overran the deadline: {}ms into the run, budget was {}ms",
+            self.stage,
+            self.elapsed.as_millis(),
+            self.budget.as_millis()
+        )
+    }
+}
+
+impl Error for DeadlineExceeded {}
+
+pub struct Stopwatch {
+    started: Instant,
+    budget: Option<Duration>,
+    timings: Vec<StageTiming>,
+}
+
+impl Stopwatch {
+    // budget is for the whole run, starting now. None never runs out
+    pub fn new(budget: Option<Duration>) -> Stopwatch {
+        Stopwatch {
+            started: Instant::now(),
+            budget,
+            timings: Vec::new(),
+        }
+    }
+
+    pub fn unlimited() -> Stopwatch {
+        Stopwatch::new(None)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    // what's left of the budget, None if there isn't one
+    pub fn remaining(&self) -> Option<Duration> {
+        self.budget
+            .map(|budget| budget.saturating_sub(self.elapsed()))
+    }
+
+    pub fn timings(&self) -> &[StageTiming] {
+        &self.timings
+    }
+
+    // runs one stage of the pipeline inside its own span, writes down how long it took and fails if that took the run
+    // past the deadline. a stage that fails on its own reports its own error, not the deadline
+    pub fn stage<T, E>(
+        &mut self,
+        stage: &'static str,
+        run: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, HTSError>
+    where
+        HTSError: From<E>,
+    {
+        self.stage_checking(stage, |_| run())
+    }
+
+    // stage(), for a stage long enough that it checks the clock itself as it goes, so it can stop part way through
+    // rather than only find out it overran once it's done
+    pub fn stage_checking<T, E>(
+        &mut self,
+        stage: &'static str,
+        run: impl FnOnce(&Stopwatch) -> Result<T, E>,
+    ) -> Result<T, HTSError>
+    where
+        HTSError: From<E>,
+    {
+        let start = Instant::now();
+        let result = {
+            let _span = info_span!("stage", name = stage).entered();
+            run(self)
+        };
+        self.record(stage, start.elapsed());
+
+        let value = result?;
+        self.check(stage)?;
+
+        Ok(value)
+    }
+
+    // for stages that can't go through stage(), like the async network calls
+    pub fn record(&mut self, stage: &'static str, elapsed: Duration) {
+        let timing = StageTiming::new(stage, elapsed);
+        debug!(stage, millis = timing.millis, "stage finished");
+
+        self.timings.push(timing);
+    }
+
+    pub fn check(&self, stage: &'static str) -> Result<(), DeadlineExceeded> {
+        match self.budget {
+            Some(budget) if self.elapsed() >= budget => Err(self.overran(stage)),
+            _ => Ok(()),
+        }
+    }
+
+    // the error for stage running out the clock, for when it had to be cut off rather than checked afterwards
+    pub fn overran(&self, stage: &'static str) -> DeadlineExceeded {
+        DeadlineExceeded {
+            stage,
+            elapsed: self.elapsed(),
+            budget: self.budget.unwrap_or_default(),
+        }
+    }
+
+    // one line per stage plus the total, for printing after a run
+    pub fn summary(&self) -> String {
+        let mut text = String::new();
+
+        for i in self.timings.iter() {
+            writeln!(text, "{:>10} {:>10.3}ms", i.stage, i.millis).unwrap();
+        }
+
+        let recorded: f64 = self.timings.iter().map(|i| i.millis).sum();
+        writeln!(text, "{:>10} {:>10.3}ms", "stages", recorded).unwrap();
+        write!(
+            text,
+            "{:>10} {:>10.3}ms",
+            "total",
+            self.elapsed().as_secs_f64() * 1000.0
+        )
+        .unwrap();
+
+        if let Some(budget) = self.budget {
+            write!(text, " of {}ms", budget.as_millis()).unwrap();
+        }
+
+        text.push('\n');
+        text
+    }
+}
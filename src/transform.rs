@@ -0,0 +1,322 @@
+// moving geometry around the page: turning it about a point, sliding it, scaling it. draw commands go through exactly,
+// since an arc or a line turned about a point is still an arc or a line and only its numbers change. rects and bitmaps
+// only go through approximately, because a turned pixel doesn't land on a whole pixel, so they're resampled
+
+use std::collections::HashMap;
+
+use crate::draw::{DrawCommand, Rect};
+
+// x' = a x + b y + tx, y' = c x + d y + ty, in page coordinates with y growing downwards
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub tx: f64,
+    pub ty: f64,
+}
+
+// a draw command's geometry in floats, so it can be moved about without rounding until it's drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    // angles in degrees, anticlockwise from the positive x axis like the page's drawArc
+    Arc {
+        centre: (f64, f64),
+        radius: f64,
+        start: f64,
+        sweep: f64,
+    },
+    Line {
+        from: (f64, f64),
+        to: (f64, f64),
+    },
+}
+
+// how a turned bitmap is read back onto whole pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resampling {
+    // every pixel takes whatever is under its middle
+    Nearest,
+    // every pixel is tried at samples by samples points and is set if at least coverage of them land on something.
+    // at a half, one pixel wide lines break up when they're turned onto a diagonal. at a quarter they stay whole but
+    // come out thicker
+    Supersample { samples: u32, coverage: f64 },
+}
+
+impl Affine {
+    pub const IDENTITY: Affine = Affine {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        tx: 0.0,
+        ty: 0.0,
+    };
+
+    pub fn translation(dx: f64, dy: f64) -> Affine {
+        Affine {
+            tx: dx,
+            ty: dy,
+            ..Affine::IDENTITY
+        }
+    }
+
+    // turns anticlockwise as seen on the page, about (0, 0). that's the way the mission turns its characters
+    pub fn rotation(degrees: f64) -> Affine {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+
+        Affine {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Affine::IDENTITY
+        }
+    }
+
+    pub fn rotation_about(centre: (f64, f64), degrees: f64) -> Affine {
+        Affine::translation(-centre.0, -centre.1)
+            .then(&Affine::rotation(degrees))
+            .then(&Affine::translation(centre.0, centre.1))
+    }
+
+    pub fn scale(factor: f64) -> Affine {
+        Affine {
+            a: factor,
+            d: factor,
+            ..Affine::IDENTITY
+        }
+    }
+
+    // this transform followed by next
+    pub fn then(&self, next: &Affine) -> Affine {
+        let (tx, ty) = next.apply((self.tx, self.ty));
+
+        Affine {
+            a: next.a * self.a + next.b * self.c,
+            b: next.a * self.b + next.b * self.d,
+            c: next.c * self.a + next.d * self.c,
+            d: next.c * self.b + next.d * self.d,
+            tx,
+            ty,
+        }
+    }
+
+    // None when it squashes everything onto a line
+    pub fn inverse(&self) -> Option<Affine> {
+        let det = self.determinant();
+
+        if det.abs() < 1e-12 {
+            return None;
+        }
+
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+
+        Some(Affine {
+            a,
+            b,
+            c,
+            d,
+            tx: -(a * self.tx + b * self.ty),
+            ty: -(c * self.tx + d * self.ty),
+        })
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    pub fn apply(&self, point: (f64, f64)) -> (f64, f64) {
+        let (x, y) = point;
+
+        (
+            self.tx + self.a * x + self.b * y,
+            self.ty + self.c * x + self.d * y,
+        )
+    }
+
+    // (scale, degrees anticlockwise) when it only turns, scales and slides, which are the only transforms that keep a
+    // circle a circle going the same way round. None for anything that skews or mirrors
+    pub fn similarity(&self) -> Option<(f64, f64)> {
+        let scale = self.determinant().max(0.0).sqrt();
+
+        if scale < 1e-12
+            || (self.a - self.d).abs() > 1e-9 * scale
+            || (self.b + self.c).abs() > 1e-9 * scale
+        {
+            return None;
+        }
+
+        Some((scale, self.b.atan2(self.a).to_degrees()))
+    }
+
+    // None when the shape is an arc and the transform doesn't keep circles circles
+    pub fn apply_shape(&self, shape: &Shape) -> Option<Shape> {
+        match *shape {
+            Shape::Line { from, to } => Some(Shape::Line {
+                from: self.apply(from),
+                to: self.apply(to),
+            }),
+            Shape::Arc {
+                centre,
+                radius,
+                start,
+                sweep,
+            } => {
+                let (scale, degrees) = self.similarity()?;
+
+                Some(Shape::Arc {
+                    centre: self.apply(centre),
+                    radius: radius * scale,
+                    start: start + degrees,
+                    sweep,
+                })
+            }
+        }
+    }
+
+    // the command moved, with its numbers rounded back to what the page can draw
+    pub fn apply_command(&self, command: &DrawCommand) -> Option<DrawCommand> {
+        self.apply_shape(&Shape::from(*command))
+            .map(|i| i.to_command())
+    }
+
+    // every pixel of the moved bitmap, worked out by taking each pixel that could be set back through the inverse to
+    // see what's under it, since pushing pixels forwards leaves holes. a pixel at (x, y) is the point (x, y)
+    pub fn apply_pixels<T: Copy>(
+        &self,
+        pixels: &HashMap<(i32, i32), T>,
+        resampling: Resampling,
+    ) -> HashMap<(i32, i32), T> {
+        let mut moved = HashMap::new();
+
+        let Some(inverse) = self.inverse() else {
+            return moved;
+        };
+
+        let corners: Vec<(f64, f64)> = pixels
+            .keys()
+            .flat_map(|i| {
+                let (x, y) = (f64::from(i.0), f64::from(i.1));
+                [
+                    (x - 0.5, y - 0.5),
+                    (x + 0.5, y - 0.5),
+                    (x - 0.5, y + 0.5),
+                    (x + 0.5, y + 0.5),
+                ]
+            })
+            .map(|i| self.apply(i))
+            .collect();
+
+        let (Some(left), Some(right), Some(top), Some(bottom)) = (
+            corners.iter().map(|i| i.0.floor() as i32).min(),
+            corners.iter().map(|i| i.0.ceil() as i32).max(),
+            corners.iter().map(|i| i.1.floor() as i32).min(),
+            corners.iter().map(|i| i.1.ceil() as i32).max(),
+        ) else {
+            return moved;
+        };
+
+        let source = |point: (f64, f64)| {
+            let (x, y) = inverse.apply(point);
+            pixels.get(&(x.round() as i32, y.round() as i32))
+        };
+
+        for x in left..=right {
+            for y in top..=bottom {
+                let (px, py) = (f64::from(x), f64::from(y));
+
+                let value = match resampling {
+                    Resampling::Nearest => source((px, py)).copied(),
+                    Resampling::Supersample { samples, coverage } => {
+                        let n = samples.max(1);
+                        let step = 1.0 / f64::from(n);
+                        let offset = |i: u32| (f64::from(i) + 0.5) * step - 0.5;
+
+                        let hits: Vec<T> = (0..n)
+                            .flat_map(|i| (0..n).map(move |j| (offset(i), offset(j))))
+                            .filter_map(|(dx, dy)| source((px + dx, py + dy)).copied())
+                            .collect();
+
+                        (!hits.is_empty() && hits.len() as f64 >= coverage * f64::from(n * n))
+                            .then(|| hits[0])
+                    }
+                };
+
+                if let Some(value) = value {
+                    moved.insert((x, y), value);
+                }
+            }
+        }
+
+        moved
+    }
+
+    // the rects moved, as one 1x1 rect per pixel, sorted
+    pub fn apply_rects(&self, rects: &[Rect], resampling: Resampling) -> Vec<Rect> {
+        let mut moved: Vec<Rect> = self
+            .apply_pixels(&pixels(rects), resampling)
+            .into_keys()
+            .map(|(x, y)| (x, y, 1, 1))
+            .collect();
+        moved.sort_unstable();
+
+        moved
+    }
+}
+
+// every pixel the rects cover
+pub fn pixels(rects: &[Rect]) -> HashMap<(i32, i32), ()> {
+    rects
+        .iter()
+        .flat_map(|i| {
+            (i.0..i.0 + i.2).flat_map(move |x| (i.1..i.1 + i.3).map(move |y| ((x, y), ())))
+        })
+        .collect()
+}
+
+impl From<DrawCommand> for Shape {
+    fn from(command: DrawCommand) -> Shape {
+        match command {
+            DrawCommand::Arc { x, y, r, s, e } => Shape::Arc {
+                centre: (f64::from(x), f64::from(y)),
+                radius: f64::from(r),
+                start: f64::from(s),
+                sweep: f64::from(e),
+            },
+            DrawCommand::Line { x1, y1, x2, y2 } => Shape::Line {
+                from: (f64::from(x1), f64::from(y1)),
+                to: (f64::from(x2), f64::from(y2)),
+            },
+        }
+    }
+}
+
+impl Shape {
+    // rounded to whole pixels and degrees, with the start angle brought back into 0 to 360 like the mission's are
+    pub fn to_command(&self) -> DrawCommand {
+        let round = |i: f64| i.round() as i32;
+
+        match *self {
+            Shape::Arc {
+                centre,
+                radius,
+                start,
+                sweep,
+            } => DrawCommand::Arc {
+                x: round(centre.0),
+                y: round(centre.1),
+                r: round(radius),
+                s: round(start).rem_euclid(360),
+                e: round(sweep),
+            },
+            Shape::Line { from, to } => DrawCommand::Line {
+                x1: round(from.0),
+                y1: round(from.1),
+                x2: round(to.0),
+                y2: round(to.1),
+            },
+        }
+    }
+}
@@ -0,0 +1,350 @@
+// works out the lines and arcs that drew a glyph from its pixels alone, for when there's no drawData to ask. the page
+// only ever draws whole-pixel lines and arcs of small whole-pixel radius, so rather than fitting anything continuous
+// this tries the page's own commands: lines between two of the glyph's pixels, and arcs round whichever centres and
+// radii most of its pixels vote for. each pass keeps the command that draws the most pixels nothing has drawn yet
+// without straying off the glyph, until what's left is too little to be a stroke
+
+use std::collections::HashSet;
+
+use crate::{
+    draw::{get_curve_coordinates, DrawCommand, Rect},
+    transform::pixels,
+};
+
+// drawData says anything with a third value under 10 is an arc, so that's as big as arcs get
+const MAX_RADIUS: i32 = 9;
+const MIN_RADIUS: i32 = 2;
+
+// a command has to draw at least this many pixels nothing else has to be worth keeping
+const MIN_GAIN: usize = 3;
+
+// every pixel a command draws that isn't on the glyph costs this many it does
+const STRAY_COST: usize = 2;
+
+// a cell and the 8 round it
+const AROUND: [(i32, i32); 9] = [
+    (0, 0),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+// how many of the best voted circles get tried properly
+const CIRCLES: usize = 12;
+
+// anything shallower than this is a line as far as a few pixels can tell. the mission's own arcs are half circles or
+// more
+const MIN_SWEEP: f64 = 90.0;
+
+// the page draws an arc a point every 8 degrees. points further apart than this round the circle are separate strokes
+const ARC_GAP: f64 = 40.0;
+
+pub fn vectorise(rects: &[Rect]) -> Vec<DrawCommand> {
+    let glyph: HashSet<(i32, i32)> = pixels(rects).into_keys().collect();
+    let lines = lines_across(&glyph);
+    let mut undrawn = glyph.clone();
+    let mut commands = Vec::new();
+
+    loop {
+        // a line wins a tie, it's the simpler of the two
+        let best = [best_line(&lines, &undrawn), best_arc(&glyph, &undrawn)]
+            .into_iter()
+            .flatten()
+            .fold(None, |best: Option<Fit>, i| match best {
+                Some(b) if b.score() >= i.score() => Some(b),
+                _ => Some(i),
+            });
+
+        let Some(fit) = best.filter(|i| i.gain >= MIN_GAIN && i.score() > 0) else {
+            break;
+        };
+
+        for i in drawn(&fit.command) {
+            undrawn.remove(&i);
+        }
+        commands.push(fit.command);
+    }
+
+    commands
+}
+
+// the pixels out of rects that arcs in commands drew, as 1x1 rects the way the page draws arcs. the rest are lines
+pub fn split_arcs(rects: &[Rect], commands: &[DrawCommand]) -> (Vec<Rect>, Vec<Rect>) {
+    let arcs: HashSet<(i32, i32)> = commands
+        .iter()
+        .filter(|i| !i.is_line())
+        .flat_map(drawn)
+        .collect();
+
+    let mut curves = Vec::new();
+    let mut lines = Vec::new();
+
+    for rect in rects {
+        if rect.2 == 1 && rect.3 == 1 && arcs.contains(&(rect.0, rect.1)) {
+            curves.push(*rect);
+        } else {
+            lines.push(*rect);
+        }
+    }
+
+    (curves, lines)
+}
+
+struct Fit {
+    command: DrawCommand,
+    // pixels it draws that nothing else has yet
+    gain: usize,
+    // pixels it draws that aren't on the glyph at all
+    stray: usize,
+}
+
+impl Fit {
+    // counts without collecting anything, since most of the commands tried are thrown away
+    fn new(
+        command: DrawCommand,
+        glyph: &HashSet<(i32, i32)>,
+        undrawn: &HashSet<(i32, i32)>,
+    ) -> Fit {
+        let (mut gain, mut stray) = (0, 0);
+
+        for rect in command.rasterize().unwrap_or_default() {
+            for i in (rect.0..rect.0 + rect.2)
+                .flat_map(|x| (rect.1..rect.1 + rect.3).map(move |y| (x, y)))
+            {
+                gain += usize::from(undrawn.contains(&i));
+                stray += usize::from(!glyph.contains(&i));
+            }
+        }
+
+        Fit {
+            command,
+            gain,
+            stray,
+        }
+    }
+
+    fn score(&self) -> i64 {
+        self.gain as i64 - (self.stray * STRAY_COST) as i64
+    }
+}
+
+fn drawn(command: &DrawCommand) -> HashSet<(i32, i32)> {
+    pixels(&command.rasterize().unwrap_or_default())
+        .into_keys()
+        .collect()
+}
+
+// a line the glyph could have been drawn with, and the pixels it draws
+struct Candidate {
+    command: DrawCommand,
+    drawn: Vec<(i32, i32)>,
+    stray: usize,
+}
+
+// every line from one of the glyph's pixels to another that stays on the glyph well enough to ever be worth drawing.
+// which pixels a line draws doesn't change from one pass to the next, only which of them are drawn already
+fn lines_across(glyph: &HashSet<(i32, i32)>) -> Vec<Candidate> {
+    let mut ends: Vec<(i32, i32)> = glyph.iter().copied().collect();
+    ends.sort_unstable();
+
+    let mut lines = Vec::new();
+
+    for (n, from) in ends.iter().enumerate() {
+        for to in ends[n + 1..].iter() {
+            // most pairs are across the glyph from each other with nothing between them. a line that misses the
+            // middle isn't worth drawing to find out
+            let middle = ((from.0 + to.0) / 2, (from.1 + to.1) / 2);
+            if !AROUND
+                .iter()
+                .any(|(dx, dy)| glyph.contains(&(middle.0 + dx, middle.1 + dy)))
+            {
+                continue;
+            }
+
+            let command = DrawCommand::Line {
+                x1: from.0,
+                y1: from.1,
+                x2: to.0,
+                y2: to.1,
+            };
+            let drawn: Vec<(i32, i32)> = drawn(&command).into_iter().collect();
+            let stray = drawn.iter().filter(|i| !glyph.contains(i)).count();
+
+            if stray * STRAY_COST < drawn.len() {
+                lines.push(Candidate {
+                    command,
+                    drawn,
+                    stray,
+                });
+            }
+        }
+    }
+
+    lines
+}
+
+// the line that starts or ends on a pixel nothing's drawn yet and scores best
+fn best_line(lines: &[Candidate], undrawn: &HashSet<(i32, i32)>) -> Option<Fit> {
+    let mut best: Option<Fit> = None;
+
+    for line in lines.iter() {
+        let DrawCommand::Line { x1, y1, x2, y2 } = line.command else {
+            continue;
+        };
+        if !undrawn.contains(&(x1, y1)) && !undrawn.contains(&(x2, y2)) {
+            continue;
+        }
+
+        let fit = Fit {
+            command: line.command,
+            gain: line.drawn.iter().filter(|i| undrawn.contains(i)).count(),
+            stray: line.stray,
+        };
+
+        if best.as_ref().is_none_or(|b| fit.score() > b.score()) {
+            best = Some(fit);
+        }
+    }
+
+    best
+}
+
+// every pixel nothing's drawn yet votes for each circle it could be on. the best few circles are cut down to the run
+// of the glyph's pixels round them that has the most in it
+fn best_arc(glyph: &HashSet<(i32, i32)>, undrawn: &HashSet<(i32, i32)>) -> Option<Fit> {
+    let (left, right) = (
+        undrawn.iter().map(|i| i.0).min()?,
+        undrawn.iter().map(|i| i.0).max()?,
+    );
+    let (top, bottom) = (
+        undrawn.iter().map(|i| i.1).min()?,
+        undrawn.iter().map(|i| i.1).max()?,
+    );
+
+    // every centre within MAX_RADIUS of the pixels, and every radius, laid out flat
+    let (x0, y0) = (left - MAX_RADIUS, top - MAX_RADIUS);
+    let (width, height) = (
+        right - left + 2 * MAX_RADIUS + 1,
+        bottom - top + 2 * MAX_RADIUS + 1,
+    );
+    let radii = MAX_RADIUS - MIN_RADIUS + 1;
+    let mut votes = vec![0usize; (width * height * radii) as usize];
+
+    // how far round a pixel every centre close enough to have it on a circle is, and the radius of that circle
+    let around = -MAX_RADIUS..=MAX_RADIUS;
+    let offsets: Vec<(i32, i32, i32)> = around
+        .clone()
+        .flat_map(|dx| {
+            around
+                .clone()
+                .map(move |dy| (dx, dy, f64::from(dx).hypot(f64::from(dy)).round() as i32))
+        })
+        .filter(|i| (MIN_RADIUS..=MAX_RADIUS).contains(&i.2))
+        .collect();
+
+    for (px, py) in undrawn.iter() {
+        for (dx, dy, r) in offsets.iter() {
+            let (x, y) = (px - dx - x0, py - dy - y0);
+            votes[((y * width + x) * radii + r - MIN_RADIUS) as usize] += 1;
+        }
+    }
+
+    // a big circle passes through more pixels just by being longer, so what counts is the share of the cells a circle
+    // that size could have had votes from that did vote
+    let mut cells = vec![0usize; radii as usize];
+    for (_, _, r) in offsets.iter() {
+        cells[(r - MIN_RADIUS) as usize] += 1;
+    }
+    let share = |n: usize, votes: usize| votes as f64 / cells[n % radii as usize] as f64;
+    let mut circles: Vec<(usize, usize)> =
+        votes.into_iter().enumerate().filter(|i| i.1 > 0).collect();
+    circles.sort_unstable_by(|a, b| {
+        share(b.0, b.1)
+            .total_cmp(&share(a.0, a.1))
+            .then(a.0.cmp(&b.0))
+    });
+
+    let circles = circles.into_iter().map(|(n, _)| {
+        let (n, r) = (n as i32 / radii, n as i32 % radii);
+        (x0 + n % width, y0 + n / width, r + MIN_RADIUS)
+    });
+
+    let mut best: Option<Fit> = None;
+
+    for (x, y, r) in circles.take(CIRCLES) {
+        let Some((start, end)) = arc_along(glyph, x, y, r) else {
+            continue;
+        };
+
+        // the page only puts a point down every 8 degrees from where the arc starts, so where exactly it starts
+        // changes which pixels it lands on
+        for shift in 0..8 {
+            let s = start.floor() as i32 - shift;
+            let arc = DrawCommand::Arc {
+                x,
+                y,
+                r,
+                s: s.rem_euclid(360),
+                e: (end - f64::from(s)).ceil() as i32,
+            };
+            let fit = Fit::new(arc, glyph, undrawn);
+
+            if best.as_ref().is_none_or(|b| fit.score() > b.score()) {
+                best = Some(fit);
+            }
+        }
+    }
+
+    best
+}
+
+// where the longest unbroken run of the glyph's pixels round the circle at (x, y) of radius r starts and ends, in
+// degrees. the end can be past 360
+fn arc_along(glyph: &HashSet<(i32, i32)>, x: i32, y: i32, r: i32) -> Option<(f64, f64)> {
+    let circle = get_curve_coordinates(x, y, r, 0, 360).ok()?;
+
+    // degrees anticlockwise from the right, the way the page measures its arcs. y grows downwards
+    let mut angles: Vec<f64> = circle
+        .iter()
+        .filter(|i| glyph.contains(&(i.0, i.1)))
+        .map(|i| {
+            f64::from(y - i.1)
+                .atan2(f64::from(i.0 - x))
+                .to_degrees()
+                .rem_euclid(360.0)
+        })
+        .collect();
+    angles.sort_by(f64::total_cmp);
+    angles.dedup();
+
+    if angles.len() < 2 {
+        return None;
+    }
+
+    // split where the gap to the next point is too big, going all the way round back to the first
+    let gaps: Vec<usize> = (0..angles.len())
+        .filter(|i| (angles[(i + 1) % angles.len()] - angles[*i]).rem_euclid(360.0) > ARC_GAP)
+        .collect();
+
+    let (start, end) = if gaps.is_empty() {
+        (0.0, 360.0)
+    } else {
+        // each run goes from just after one gap to the next gap
+        gaps.iter()
+            .enumerate()
+            .map(|(n, gap)| {
+                let first = (gap + 1) % angles.len();
+                let last = gaps[(n + 1) % gaps.len()];
+                let sweep = (angles[last] - angles[first]).rem_euclid(360.0);
+                (angles[first], angles[first] + sweep)
+            })
+            .max_by(|a, b| (a.1 - a.0).total_cmp(&(b.1 - b.0)))?
+    };
+
+    (end - start >= MIN_SWEEP).then_some((start, end))
+}
@@ -0,0 +1,99 @@
+// the normalised glyph grid: the same shape wherever, however big and however turned it's drawn
+
+use std::collections::HashSet;
+
+use hts_prog6::{
+    bitmap::SIZE,
+    draw::DrawCommand,
+    transform::{pixels, Affine, Resampling},
+    Glyph, GlyphBitmap, Rect,
+};
+
+fn draw(lines: &[(i32, i32, i32, i32)], x: i32, y: i32) -> Vec<Rect> {
+    lines
+        .iter()
+        .flat_map(|i| {
+            let line = DrawCommand::Line {
+                x1: x + i.0,
+                y1: y + i.1,
+                x2: x + i.2,
+                y2: y + i.3,
+            };
+            line.rasterize().unwrap()
+        })
+        .collect()
+}
+
+// 9 wide and 13 tall like the mission's, with their top left at (x, y)
+fn f(x: i32, y: i32) -> Vec<Rect> {
+    draw(&[(0, 0, 0, 12), (0, 0, 8, 0), (0, 6, 5, 6)], x, y)
+}
+
+fn e(x: i32, y: i32) -> Vec<Rect> {
+    draw(
+        &[(0, 0, 0, 12), (0, 0, 8, 0), (0, 6, 5, 6), (0, 12, 8, 12)],
+        x,
+        y,
+    )
+}
+
+fn seven(x: i32, y: i32) -> Vec<Rect> {
+    draw(&[(0, 0, 8, 0), (8, 0, 2, 12)], x, y)
+}
+
+#[test]
+fn fingerprints_stay_put() {
+    let mut bitmap = GlyphBitmap::default();
+    for i in 0..SIZE as i32 {
+        bitmap.set(i, i);
+        bitmap.set(0, i);
+    }
+
+    // changing how fingerprints are worked out breaks anything saved with the old ones
+    assert_eq!(
+        bitmap.fingerprint(),
+        GlyphBitmap::fit(bitmap.cells()).fingerprint()
+    );
+    assert_eq!(
+        format!("{:?}", GlyphBitmap::default()),
+        "GlyphBitmap(0c8210784d8af5a5)"
+    );
+}
+
+#[test]
+fn moving_and_scaling_give_the_same_bitmap() {
+    let here = GlyphBitmap::fit(pixels(&f(100, 100)).into_keys());
+    let there = GlyphBitmap::fit(pixels(&f(531, 77)).into_keys());
+    assert_eq!(here, there);
+
+    let doubled = Affine::scale(2.0).apply_rects(&f(100, 100), Resampling::Nearest);
+    let cells: HashSet<(i32, i32)> = pixels(&doubled).into_keys().collect();
+    assert!(GlyphBitmap::fit(cells).distance(&here) < 16);
+
+    // the longer side fills the grid
+    assert_eq!(here.rows(), Some((0, SIZE as i32 - 1)));
+}
+
+#[test]
+fn turned_glyphs_stay_nearest_their_upright_bitmap() {
+    let upright =
+        [f(300, 300), e(300, 300), seven(300, 300)].map(|i| GlyphBitmap::of(&Glyph::new(i, 0)));
+
+    for index in 0..36 {
+        let glyph = Glyph::new(f(300, 300), 0);
+        let turn = Affine::rotation_about(glyph.centroid, f64::from(index * 10));
+
+        // turned the way the mission would have drawn it as its index-th character
+        let turned = Glyph::new(turn.apply_rects(&glyph.rects, Resampling::Nearest), index);
+        let bitmap = GlyphBitmap::of(&turned);
+        let distances = upright.map(|i| bitmap.distance(&i));
+
+        assert!(
+            distances[0] < distances[1] && distances[0] < distances[2],
+            "{} degrees: F, E and 7 are {:?} cells out\n{}",
+            index * 10,
+            distances,
+            bitmap
+        );
+    }
+}
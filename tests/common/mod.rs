@@ -0,0 +1,30 @@
+// helpers more than one test file draws on
+
+use std::collections::HashSet;
+
+use hts_prog6::{synth::character, Glyph, Rect};
+
+// c drawn on its own, turned by turn, with the frame the reading order would give it, and every pixel of it that's an
+// arc. it's put a fraction of a pixel off somewhere different for each turn, so rounding doesn't always fall the same
+// way
+pub fn drawn(c: u8, turn: i32) -> (Glyph, HashSet<(i32, i32)>) {
+    let at = (
+        200.0 + f64::from(turn % 3) / 3.0,
+        200.0 + f64::from(turn % 4) / 4.0,
+    );
+    let (mut curves, mut lines): (Vec<Rect>, Vec<Rect>) = (Vec::new(), Vec::new());
+
+    for command in character(c, turn, at) {
+        if command.is_line() {
+            lines.extend(command.rasterize().unwrap());
+        } else {
+            curves.extend(command.rasterize().unwrap());
+        }
+    }
+
+    let arcs = curves.iter().map(|i| (i.0, i.1)).collect();
+    let mut glyph = Glyph::new([curves, lines].concat(), 0);
+    glyph.frame.rotation = f64::from(turn);
+
+    (glyph, arcs)
+}
@@ -0,0 +1,77 @@
+// telling an edit to the page's drawing script apart from the page just being laid out differently
+
+use hts_prog6::{
+    fingerprint::{check, drawing_script, ChangeKind, DiffLine, Fingerprint, KNOWN_GOOD_SCRIPT},
+    synth::{generate, Layout},
+};
+
+// a page serving script, with some drawData in it
+fn page(script: &str) -> String {
+    let script = script.replace("new Array(...)", "new Array(402,106,402,94,3,0,270)");
+    format!(
+        "<html><head><script type=\"text/javascript\">\n{}</script></head></html>",
+        script
+    )
+}
+
+#[test]
+fn the_same_script_matches() {
+    let found = check(&page(KNOWN_GOOD_SCRIPT)).unwrap();
+
+    assert_eq!(found.combined(), Fingerprint::known_good().combined());
+}
+
+#[test]
+fn whitespace_and_comments_dont_count() {
+    let reformatted = KNOWN_GOOD_SCRIPT
+        .replace("\n  ", "\n\t\t")
+        .replace(" = ", "=")
+        .replace("{\n", "{ // opens\n")
+        .replace(";\n", ";\n\n");
+
+    assert_ne!(reformatted, KNOWN_GOOD_SCRIPT);
+    assert!(check(&page(&reformatted)).is_ok());
+}
+
+#[test]
+fn a_changed_constant_is_reported_with_a_diff() {
+    let edited = KNOWN_GOOD_SCRIPT.replace("ss += 8", "ss += 6");
+    assert_ne!(edited, KNOWN_GOOD_SCRIPT);
+
+    let changed = check(&page(&edited)).unwrap_err();
+    assert_eq!(changed.changes.len(), 1, "{}", changed);
+
+    let change = &changed.changes[0];
+    assert_eq!(change.name, "drawArc");
+    assert_eq!(change.kind, ChangeKind::Modified);
+    assert_ne!(change.expected, change.found);
+
+    let removed: Vec<&String> = change
+        .diff
+        .iter()
+        .filter_map(|i| match i {
+            DiffLine::Removed(s) => Some(s),
+            _ => None,
+        })
+        .collect();
+    let added: Vec<&String> = change
+        .diff
+        .iter()
+        .filter_map(|i| match i {
+            DiffLine::Added(s) => Some(s),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(removed, ["ss+=8){"], "{}", changed);
+    assert_eq!(added, ["ss+=6){"], "{}", changed);
+}
+
+#[test]
+fn a_captured_script_matches_the_page_it_came_from() {
+    let page = generate(3, Layout::REALISTIC).html();
+    let captured = drawing_script(&page);
+
+    assert!(captured.contains("new Array(...)"));
+    assert!(check(&format!("<script>{}</script>", captured)).is_ok());
+}
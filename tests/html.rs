@@ -0,0 +1,29 @@
+// the single file html report of a decoded page
+
+use hts_prog6::{
+    decode_page, html,
+    synth::{generate, Layout},
+};
+
+#[test]
+fn the_report_stands_on_its_own() {
+    let decoded = decode_page(&generate(0, Layout::REALISTIC).html()).unwrap();
+    let answer = decoded.answer();
+    let report = html::report(&answer, &decoded.all_rects(), &decoded.glyphs);
+
+    assert!(report.starts_with("<!DOCTYPE html>"));
+    assert!(report.ends_with("</html>\n"));
+    assert!(report.contains(&format!("<h1>decoded: {}</h1>", answer)));
+
+    // nothing it has to fetch or run to be read
+    for outside in ["<script", "src=", "http:", "https:", "<link", "@import"] {
+        assert!(!report.contains(outside), "{}", outside);
+    }
+
+    // a div per character on the canvas, and a row per character in the table
+    assert_eq!(
+        report.matches("<div class=\"glyph\"").count(),
+        decoded.glyphs.len()
+    );
+    assert_eq!(report.matches("<tr").count(), decoded.glyphs.len() + 1);
+}
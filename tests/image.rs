@@ -0,0 +1,53 @@
+// drawing the rects back out as a picture
+
+use hts_prog6::{
+    image::{render_canvas, render_segment, RenderOptions, MAX_SCALE},
+    Glyph,
+};
+
+fn options(scale: i32) -> RenderOptions {
+    RenderOptions {
+        scale,
+        colour_segments: true,
+        index_labels: true,
+        char_labels: true,
+    }
+}
+
+#[test]
+fn a_scale_too_big_to_draw_is_an_error_not_an_overflow() {
+    let glyph = Glyph::new(vec![(10, 10, 5, 1), (10, 11, 1, 6)], 0);
+
+    let canvas = render_canvas(
+        &glyph.rects,
+        std::slice::from_ref(&glyph),
+        options(MAX_SCALE),
+    )
+    .unwrap();
+    assert_eq!(canvas.pixels.len(), canvas.width * canvas.height);
+
+    assert!(render_canvas(&glyph.rects, &[], options(i32::MAX)).is_err());
+    assert!(render_segment(&glyph, options(i32::MAX / 4)).is_err());
+
+    // a drawing that's huge before it's even scaled
+    let far = [(i32::MIN / 2, 0, 1, 1), (i32::MAX / 2, 0, 1, 1)];
+    assert!(render_canvas(&far, &[], options(1)).is_err());
+}
+
+#[test]
+fn labels_on_the_top_row_fit_in_the_picture() {
+    // the topmost character on the canvas, and its label
+    let glyph = Glyph::new(vec![(10, 10, 5, 1), (10, 11, 1, 6)], 88);
+    let white = |pixels: &[[u8; 3]]| pixels.iter().filter(|i| **i == [255, 255, 255]).count();
+    let options = RenderOptions {
+        char_labels: false,
+        ..options(2)
+    };
+
+    // the cut out character has its label underneath, where there's always room
+    let canvas = render_canvas(&glyph.rects, std::slice::from_ref(&glyph), options).unwrap();
+    let segment = render_segment(&glyph, options).unwrap();
+
+    assert!(white(&segment.pixels) > 0);
+    assert_eq!(white(&canvas.pixels), white(&segment.pixels));
+}
@@ -0,0 +1,127 @@
+// learning to read glyphs from labelled ones, and keeping the model that comes out of it
+
+use std::sync::{Arc, OnceLock};
+
+use hts_prog6::{
+    decode_page_within,
+    model::{split, synthetic_examples_from, Example, Model, ModelError, FEATURES, VERSION},
+    synth::{generate, Layout},
+    timing::Stopwatch,
+    Strategy,
+};
+
+// the synthetic pages the model is trained on. the pages it's tested on come after them
+const TRAINING: std::ops::Range<u64> = 0..3;
+
+// trained once and shared, since it's the slow part of every test here
+fn trained() -> &'static (Model, Vec<Example>) {
+    static TRAINED: OnceLock<(Model, Vec<Example>)> = OnceLock::new();
+
+    TRAINED.get_or_init(|| {
+        let (training, held_out) = split(synthetic_examples_from(TRAINING).unwrap(), 0.2);
+        (Model::train(&training), held_out)
+    })
+}
+
+#[test]
+fn reads_characters_it_wasnt_trained_on() {
+    let (model, held_out) = trained();
+    let evaluation = model.evaluate(held_out);
+
+    assert!(evaluation.total > 50);
+    assert!(evaluation.accuracy() >= 0.95, "{:?}", evaluation);
+}
+
+#[test]
+fn comes_back_the_same_from_its_file() {
+    let (model, _) = trained();
+    let path = std::env::temp_dir().join(format!("hts-model-{}.json", std::process::id()));
+
+    model.save(&path).unwrap();
+    let loaded = Model::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(&loaded.unwrap(), model);
+}
+
+#[test]
+fn an_old_model_is_turned_away() {
+    let (model, _) = trained();
+    let json = model.to_json().unwrap().replacen(
+        &format!("\"version\":{}", VERSION),
+        &format!("\"version\":{}", VERSION + 1),
+        1,
+    );
+
+    assert!(
+        matches!(Model::from_json(&json), Err(ModelError::Version { found }) if found == VERSION + 1)
+    );
+    assert!(matches!(
+        Model::from_json("{\"version\":1}"),
+        Err(ModelError::Format(_))
+    ));
+}
+
+#[test]
+fn a_model_the_wrong_shape_is_turned_away() {
+    let (model, _) = trained();
+    let json: serde_json::Value = serde_json::from_str(&model.to_json().unwrap()).unwrap();
+    let load = |json: &serde_json::Value| Model::from_json(&json.to_string());
+
+    let mut empty = json.clone();
+    empty["weights"][0] = serde_json::json!([]);
+    assert!(matches!(load(&empty), Err(ModelError::Shape { found: 0 })));
+
+    let mut short = json.clone();
+    short["weights"][1].as_array_mut().unwrap().pop();
+    assert!(matches!(load(&short), Err(ModelError::Shape { found }) if found == FEATURES - 1));
+
+    let mut missing = json.clone();
+    missing["weights"].as_array_mut().unwrap().pop();
+    assert!(matches!(
+        load(&missing),
+        Err(ModelError::Classes { classes, rows }) if rows == classes - 1
+    ));
+}
+
+#[test]
+fn decodes_pages_better_than_the_rules_do() {
+    let (model, _) = trained();
+    let (mut learned, mut rules, mut all) = (0, 0, 0);
+
+    // pages none of the training came from. every synthetic page segments and fits the spiral cleanly, so every
+    // character read wrong is down to whatever read it
+    for seed in TRAINING.end..TRAINING.end + 3 {
+        let page = generate(seed, Layout::REALISTIC);
+        let right = |strategy: Strategy| {
+            let decoded =
+                decode_page_within(&page.html(), &strategy, &mut Stopwatch::new(None)).unwrap();
+            assert_eq!(decoded.layout.as_ref().map(|i| i.misfits.len()), Some(0));
+
+            decoded
+                .answer()
+                .chars()
+                .zip(page.text.chars())
+                .filter(|(a, b)| a == b)
+                .count()
+        };
+
+        learned += right(Strategy::Learned(Arc::new(model.clone())));
+        rules += right(Strategy::Rules);
+        all += page.text.len();
+    }
+
+    // the model reads 98 to 100% of every page at the moment
+    assert!(
+        learned as f64 >= all as f64 * 0.97,
+        "{} of {} read right",
+        learned,
+        all
+    );
+    assert!(
+        learned > rules,
+        "{} against {} read right by the rules",
+        learned,
+        rules
+    );
+}
@@ -0,0 +1,87 @@
+// reading glyphs by shape alone, however far round they're turned
+
+use std::collections::HashSet;
+
+use hts_prog6::{moments::Descriptor, synth::ALPHABET, CharacterID, Glyph, Rect, Strategy};
+
+mod common;
+
+use common::drawn;
+
+#[test]
+fn descriptors_dont_turn_with_the_glyph() {
+    for c in ALPHABET.iter() {
+        let (glyph, arcs) = drawn(*c, 0);
+
+        // a quarter turn moves every pixel onto another one exactly
+        let turned: Vec<Rect> = glyph
+            .rects
+            .iter()
+            .flat_map(|i| (i.0..i.0 + i.2).flat_map(move |x| (i.1..i.1 + i.3).map(move |y| (x, y))))
+            .map(|(x, y)| (y, -x, 1, 1))
+            .collect();
+        let turned_arcs: HashSet<(i32, i32)> = arcs.iter().map(|(x, y)| (*y, -*x)).collect();
+
+        let a = Descriptor::of(&glyph, &arcs).unwrap();
+        let b = Descriptor::of(&Glyph::new(turned, 0), &turned_arcs).unwrap();
+
+        for (a, b) in
+            a.hu.iter()
+                .zip(b.hu.iter())
+                .chain(a.radial.iter().zip(b.radial.iter()))
+        {
+            assert!(
+                (a - b).abs() < 1e-4,
+                "{}: {:?} turned is {:?}",
+                *c as char,
+                a,
+                b
+            );
+        }
+    }
+}
+
+#[test]
+fn every_character_reads_at_turns_the_references_skip() {
+    let (mut right, mut all) = (0, 0);
+
+    for c in ALPHABET.iter() {
+        for turn in (5..360).step_by(20) {
+            let (glyph, arcs) = drawn(*c, turn);
+            let read = glyph.identify_char(&arcs, &Strategy::Moments).unwrap();
+
+            all += 1;
+            right += usize::from(read.val.as_bytes()[0] == *c);
+        }
+    }
+
+    assert!(right * 100 >= all * 95, "{} of {} read right", right, all);
+}
+
+#[test]
+fn a_wrong_angle_only_matters_for_six_and_nine() {
+    for c in ALPHABET.iter().filter(|i| !b"69".contains(i)) {
+        let (mut glyph, arcs) = drawn(*c, 40);
+        glyph.frame = glyph.frame.turned(150.0);
+
+        assert_eq!(
+            glyph
+                .identify_char(&arcs, &Strategy::Moments)
+                .unwrap()
+                .val
+                .as_bytes()[0],
+            *c,
+            "{} with its angle off",
+            *c as char
+        );
+    }
+
+    // the same shape turned round, so the angle is all there is to go on
+    for (c, id) in [(b'6', CharacterID::Six), (b'9', CharacterID::Nine)] {
+        let (glyph, arcs) = drawn(c, 200);
+        let recognition = glyph.recognise(&arcs, &Strategy::Moments).unwrap();
+
+        assert_eq!(recognition.character.id, id);
+        assert!(recognition.confidence <= 0.5);
+    }
+}
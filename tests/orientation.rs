@@ -0,0 +1,137 @@
+// telling how far round a glyph is turned from its shape, and holding the reading order to it
+
+use hts_prog6::{
+    decode_page,
+    orientation::{Orientation, TOLERANCE},
+    spiral::Placement,
+    synth::{generate, Layout, ALPHABET},
+    Strategy,
+};
+
+mod common;
+
+use common::drawn;
+
+#[test]
+fn the_shape_gives_the_turn() {
+    let (mut close, mut all) = (0, 0);
+
+    // the ones that fit either way up are left to shapes_that_fit_either_way_up_go_with_the_reading_order
+    for c in ALPHABET.iter().filter(|i| !b"0689".contains(i)) {
+        for turn in (3..360).step_by(30) {
+            let (glyph, _) = drawn(*c, turn);
+            let orientation = Orientation::of(&glyph).unwrap();
+
+            all += 1;
+            close += usize::from(orientation.agrees());
+        }
+    }
+
+    assert_eq!(
+        close, all,
+        "{} of {} within {} degrees",
+        close, all, TOLERANCE
+    );
+}
+
+#[test]
+fn a_slipped_reading_order_costs_confidence() {
+    let (glyph, arcs) = drawn(b'E', 40);
+    let right = glyph.recognise(&arcs, &Strategy::Rules).unwrap();
+
+    // two glyphs missed before this one. one is less than the shape can tell
+    let mut slipped = glyph.clone();
+    slipped.frame = slipped.frame.turned(20.0);
+    let wrong = slipped.recognise(&arcs, &Strategy::Rules).unwrap();
+
+    let orientation = wrong.orientation.unwrap();
+    assert!(!orientation.agrees(), "{:?}", orientation);
+    assert!(
+        (orientation.disagreement - 20.0).abs() < TOLERANCE,
+        "{:?}",
+        orientation
+    );
+
+    assert!(right.orientation.unwrap().agrees());
+    assert!(
+        wrong.confidence < right.confidence,
+        "{} against {}",
+        wrong.confidence,
+        right.confidence
+    );
+    assert!(
+        wrong
+            .path
+            .iter()
+            .any(|i| i.contains("out from the reading order")),
+        "{:?}",
+        wrong.path.steps
+    );
+}
+
+#[test]
+fn shapes_that_fit_either_way_up_go_with_the_reading_order() {
+    // a 6 upside down is a 9
+    for c in b"069" {
+        let (mut glyph, _) = drawn(*c, 70);
+        glyph.frame = glyph.frame.turned(180.0);
+
+        // which way up it picks is what matters here, not how close it gets
+        let orientation = Orientation::of(&glyph).unwrap();
+        assert!(
+            orientation.disagreement < 45.0,
+            "{}: {:?}",
+            *c as char,
+            orientation
+        );
+    }
+}
+
+#[test]
+fn clean_pages_never_disagree() {
+    for seed in 0..2 {
+        let page = generate(seed, Layout::REALISTIC);
+        let decoded = decode_page(&page.html()).unwrap();
+
+        for glyph in decoded.glyphs.iter() {
+            // the spiral vouches for every one of them, so the shape isn't asked
+            let recognition = glyph.recognition.as_ref().unwrap();
+            assert_eq!(
+                recognition.orientation, None,
+                "seed {} glyph {}",
+                seed, glyph.index
+            );
+
+            // and wouldn't have disagreed if it had been
+            let orientation = Orientation::of(glyph).unwrap();
+            assert!(
+                orientation.agrees(),
+                "seed {} glyph {}: {:?}",
+                seed,
+                glyph.index,
+                orientation
+            );
+        }
+    }
+}
+
+#[test]
+fn the_shape_is_only_asked_when_the_spiral_cant_vouch_for_the_glyph() {
+    let (mut glyph, _) = drawn(b'A', 120);
+    let placed = |angle: f64| Placement {
+        turn: 0,
+        angle,
+        along: angle,
+        residual: 0.0,
+    };
+
+    // no spiral
+    assert!(Orientation::if_needed(&glyph).is_some());
+
+    glyph.placement = Some(placed(120.4));
+    assert_eq!(Orientation::if_needed(&glyph), None);
+
+    // a character's place further round than the reading order says
+    glyph.placement = Some(placed(130.0));
+    assert!(Orientation::if_needed(&glyph).is_some());
+}
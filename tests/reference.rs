@@ -0,0 +1,44 @@
+// the page's own drawing functions, run in boa, against the rust ports of them
+#![cfg(feature = "js-reference")]
+
+use hts_prog6::{
+    draw::commands,
+    reference::{Reference, ReferenceError},
+    synth::{generate, Layout},
+};
+
+#[test]
+fn our_commands_draw_the_same_as_the_page_does() {
+    let page = generate(3, Layout::REALISTIC);
+    let commands = commands(&page.draw_data).unwrap();
+    let mut js = Reference::from_page(&page.html()).unwrap();
+
+    // enough for every character several times over. boa is slow in a debug build
+    let mismatches = js.cross_check(&commands[..200]).unwrap();
+    assert!(mismatches.is_empty(), "{:?}", mismatches.first());
+}
+
+#[test]
+fn a_page_that_never_stops_is_cut_off() {
+    let page = "<script>
+        function drawArc(x, y, r, s, e) { while (true) {} }
+        function drawLine(x1, y1, x2, y2) { return drawLine(x1, y1, x2, y2); }
+    </script>";
+    let mut js = Reference::from_page(page).unwrap();
+    let arc = commands(&[402, 106, 3, 0, 270]).unwrap();
+    let line = commands(&[400, 100, 410, 100]).unwrap();
+
+    assert!(matches!(js.rasterize(&arc), Err(ReferenceError::Script(_))));
+    assert!(matches!(
+        js.rasterize(&line),
+        Err(ReferenceError::Script(_))
+    ));
+}
+
+#[test]
+fn a_page_without_the_drawing_functions_is_turned_away() {
+    assert!(matches!(
+        Reference::from_page("<script>function drawLine() {}</script>"),
+        Err(ReferenceError::FunctionNotFound("drawArc"))
+    ));
+}
@@ -0,0 +1,68 @@
+// the json a decode report is written out as. scripts read it, so the fields it has are pinned here
+
+use serde_json::Value;
+
+use hts_prog6::{
+    decode_page,
+    synth::{generate, Layout},
+};
+
+fn keys(value: &Value) -> Vec<&str> {
+    let mut keys: Vec<&str> = value
+        .as_object()
+        .expect("an object")
+        .keys()
+        .map(|i| i.as_str())
+        .collect();
+    keys.sort_unstable();
+    keys
+}
+
+#[test]
+fn the_json_has_the_fields_scripts_read() {
+    let page = generate(0, Layout::REALISTIC);
+    let decoded = decode_page(&page.html()).unwrap();
+    let json = serde_json::to_value(decoded.report()).unwrap();
+
+    assert_eq!(
+        keys(&json),
+        ["answer", "glyphs", "misfits", "ring", "spiral", "timings"]
+    );
+    assert_eq!(json["answer"], Value::from(decoded.answer()));
+
+    let glyphs = json["glyphs"].as_array().unwrap();
+    assert_eq!(glyphs.len(), page.text.len());
+    assert_eq!(
+        keys(&glyphs[0]),
+        [
+            "along",
+            "alternatives",
+            "angle",
+            "bounding_box",
+            "centroid",
+            "confidence",
+            "id",
+            "index",
+            "measured_angle",
+            "orientation",
+            "section",
+            "spiral_residual",
+            "turn",
+            "value"
+        ]
+    );
+    assert_eq!(glyphs[0]["bounding_box"].as_array().unwrap().len(), 4);
+    assert_eq!(glyphs[0]["centroid"].as_array().unwrap().len(), 2);
+    assert!(glyphs[0]["measured_angle"].is_number());
+
+    assert_eq!(
+        keys(&json["ring"]),
+        ["centre", "inner_radius", "outer_radius", "radius"]
+    );
+    assert_eq!(keys(&json["spiral"]), ["centre", "pitch", "start_radius"]);
+    assert!(json["misfits"].as_array().unwrap().is_empty());
+
+    let timings = json["timings"].as_array().unwrap();
+    assert!(!timings.is_empty());
+    assert_eq!(keys(&timings[0]), ["millis", "stage"]);
+}
@@ -0,0 +1,128 @@
+// the quadrant walk over RectIndex has to split a page the same way the walk did when every step scanned every rect
+
+use std::collections::HashSet;
+
+use hts_prog6::{
+    draw::commands,
+    rasterize, segment,
+    segment::position,
+    synth::{generate, Layout},
+    Rect, Section,
+};
+
+// the same test segment.rs's touching makes, written out again so the scan below doesn't share anything with the
+// index it's checked against
+fn touching(cc: Rect, i: Rect) -> bool {
+    let (cc_x, cc_y, cc_w, cc_h) = cc;
+    let (x, y, w, h) = i;
+
+    (x == cc_x && y == cc_y && (w != cc_w || h != cc_h))
+        || (x == cc_w + cc_x && y == cc_y - h)
+        || (x == cc_x - w && y == cc_y - cc_h)
+        || (x == cc_x - w && y == cc_y - h)
+        || (cc_y == y - 1 && (cc_x == x + 1 || x == cc_x + 1))
+        || (y + 1 == cc_y && (x + 1 == cc_x || cc_x + 1 == x))
+        || ((x == cc_x - 1 || x == cc_x + 1) && w == cc_w && h == cc_h && y == cc_y)
+        || ((y == cc_y - 1 || y == cc_y + 1) && w == cc_w && h == cc_h && x == cc_x)
+        || ((cc_x <= w + x && cc_x >= x) && (y <= cc_y + cc_h && y >= cc_y))
+        || ((y >= cc_y && y <= cc_y + cc_h) && (x >= cc_x && x <= cc_x + cc_w))
+        || ((x <= cc_x + cc_w && x >= cc_x - w) && (y >= cc_y - h && y <= cc_y - h + cc_h))
+}
+
+// everything in rects connected to start, found by checking every rect against every one found so far
+fn grow(rects: &[Rect], start: Rect) -> HashSet<Rect> {
+    let mut found = vec![start];
+    let mut seen: HashSet<Rect> = HashSet::from([start]);
+    let mut next = 0;
+
+    while let Some(cc) = found.get(next).copied() {
+        next += 1;
+
+        for i in rects {
+            if touching(cc, *i) && seen.insert(*i) {
+                found.push(*i);
+            }
+        }
+    }
+
+    seen
+}
+
+// the next character's first rect, looking at every rect left
+fn get_next(section: Section, cc: Rect, rects: &[Rect]) -> Option<Rect> {
+    let mut next = None;
+    let mut best = match section {
+        Section::A | Section::B => i32::MAX,
+        Section::C | Section::D => i32::MIN,
+    };
+
+    for i in rects {
+        let (x, y) = (i.0, i.1);
+        let better = match section {
+            Section::A => x <= cc.0 && y >= cc.1 && y <= best,
+            Section::B => x >= cc.0 && y >= cc.1 && x <= best,
+            Section::C => x >= cc.0 && y <= cc.1 && y >= best,
+            Section::D => x <= cc.0 && y <= cc.1 && x >= best,
+        };
+
+        if better {
+            best = match section {
+                Section::A | Section::C => y,
+                Section::B | Section::D => x,
+            };
+            next = Some(*i);
+        }
+    }
+
+    next
+}
+
+// the walk as it was before the index: every step goes over every rect no character has claimed yet
+fn linear_segment(curves: &[Rect], lines: &[Rect]) -> Option<Vec<HashSet<Rect>>> {
+    let mut left = [curves, lines].concat();
+    let top = *left.iter().min_by_key(|i| i.1)?;
+
+    let mut glyphs = vec![grow(&left, top)];
+    left.retain(|i| !glyphs[0].contains(i));
+    let mut prev = top;
+
+    for i in 1..253 {
+        let next = get_next(position(i).1, prev, &left)?;
+        let glyph = grow(&left, next);
+
+        left.retain(|i| !glyph.contains(i));
+        glyphs.push(glyph);
+        prev = next;
+    }
+
+    Some(glyphs)
+}
+
+#[test]
+fn the_index_splits_pages_the_same_as_scanning_every_rect() {
+    for seed in 0..3 {
+        let page = generate(seed, Layout::REALISTIC);
+        let (curves, lines) = rasterize(&commands(&page.draw_data).unwrap()).unwrap();
+
+        let indexed = segment(&curves, &lines).ok();
+        let scanned = linear_segment(&curves, &lines);
+
+        let (Some(indexed), Some(scanned)) = (indexed, scanned) else {
+            panic!("seed {} didn't segment", seed);
+        };
+        assert_eq!(indexed.len(), scanned.len(), "seed {}", seed);
+
+        for (glyph, rects) in indexed.iter().zip(scanned.iter()) {
+            let found: HashSet<Rect> = glyph.rects.iter().copied().collect();
+
+            assert_eq!(
+                glyph.rects.len(),
+                found.len(),
+                "seed {} glyph {} has a rect twice",
+                seed,
+                glyph.index
+            );
+            assert_eq!(&found, rects, "seed {} glyph {}", seed, glyph.index);
+        }
+    }
+}
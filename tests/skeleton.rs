@@ -0,0 +1,156 @@
+// thinning glyphs to their strokes and reading the graph off them
+
+use std::collections::HashSet;
+
+use hts_prog6::{
+    bitmap::SIZE,
+    draw::commands,
+    rasterize, segment,
+    skeleton::{thin, NodeKind, StrokeGraph},
+    synth::{generate, Layout},
+    GlyphBitmap,
+};
+
+fn bitmap(rows: &[&str]) -> GlyphBitmap {
+    let mut bitmap = GlyphBitmap::default();
+
+    for (y, row) in rows.iter().enumerate() {
+        for (x, cell) in row.chars().enumerate() {
+            if cell == '#' {
+                bitmap.set(x as i32, y as i32);
+            }
+        }
+    }
+
+    bitmap
+}
+
+// a ring of the given radius around the middle of the grid, thick enough to need thinning
+fn ring(radius: f64) -> GlyphBitmap {
+    let mut bitmap = GlyphBitmap::default();
+    let middle = (SIZE as f64 - 1.0) / 2.0;
+
+    for y in 0..SIZE as i32 {
+        for x in 0..SIZE as i32 {
+            let distance = (f64::from(x) - middle).hypot(f64::from(y) - middle);
+            if (distance - radius).abs() < 1.0 {
+                bitmap.set(x, y);
+            }
+        }
+    }
+
+    bitmap
+}
+
+#[test]
+fn thick_strokes_thin_to_one_cell() {
+    let bar = bitmap(&[
+        "",
+        "",
+        "",
+        "",
+        "",
+        "",
+        "  ############",
+        "  ############",
+        "  ############",
+    ]);
+    let thinned = thin(&bar);
+
+    assert_eq!(thinned.rows(), Some((7, 7)), "\n{}", thinned);
+    assert!(thinned.count() >= 8, "\n{}", thinned);
+
+    // already thin stays as it is
+    assert_eq!(thin(&thinned), thinned);
+}
+
+#[test]
+fn a_plus_has_four_ends_and_a_junction() {
+    let plus = bitmap(&[
+        "",
+        "       ##",
+        "       ##",
+        "       ##",
+        "       ##",
+        "       ##",
+        " ##############",
+        " ##############",
+        "       ##",
+        "       ##",
+        "       ##",
+        "       ##",
+        "       ##",
+    ]);
+    let graph = StrokeGraph::of(&plus);
+
+    assert_eq!(graph.count(NodeKind::End), 4, "\n{}", graph.skeleton());
+    assert_eq!(graph.count(NodeKind::Junction), 1, "\n{}", graph.skeleton());
+    assert_eq!(graph.edges.len(), 4);
+}
+
+#[test]
+fn corners_are_not_curves() {
+    let mut rows = vec!["  ##"; 13];
+    rows.extend(["  ############", "  ############"]);
+    let graph = StrokeGraph::of(&bitmap(&rows));
+
+    assert_eq!(graph.count(NodeKind::End), 2, "\n{}", graph.skeleton());
+    assert_eq!(graph.count(NodeKind::Junction), 0);
+
+    // turns as hard as a ring, but only in one place
+    assert!(graph.max_curvature() < 0.05, "{:?}", graph.edges);
+}
+
+#[test]
+fn rings_close_on_themselves() {
+    for radius in [4.0, 6.0] {
+        let graph = StrokeGraph::of(&ring(radius));
+
+        assert_eq!(graph.count(NodeKind::Loop), 1, "\n{}", graph.skeleton());
+        assert_eq!(graph.edges.len(), 1);
+
+        let edge = &graph.edges[0];
+        assert_eq!(edge.from, edge.to);
+        assert!(
+            (edge.curvature - 1.0 / radius).abs() < 0.5 / radius,
+            "radius {} came out with curvature {}",
+            radius,
+            edge.curvature
+        );
+    }
+}
+
+#[test]
+fn strokes_know_which_ink_is_arc() {
+    let (curves, lines) =
+        rasterize(&commands(&generate(6, Layout::REALISTIC).draw_data).unwrap()).unwrap();
+    let arcs: HashSet<(i32, i32)> = curves.iter().map(|i| (i.0, i.1)).collect();
+
+    for glyph in segment(&curves, &lines).unwrap().iter().take(36) {
+        let strokes = glyph.strokes(&arcs);
+        let arc_pixels = glyph
+            .rects
+            .iter()
+            .any(|i| i.2 == 1 && i.3 == 1 && arcs.contains(&(i.0, i.1)));
+
+        // a glyph with no arc pixels has no arc strokes, and one drawn only by arcs has nothing else
+        if !arc_pixels {
+            assert!(
+                strokes.edges.iter().all(|i| i.arc == 0.0),
+                "glyph {}",
+                glyph.index
+            );
+        }
+        if glyph
+            .rects
+            .iter()
+            .all(|i| i.2 == 1 && i.3 == 1 && arcs.contains(&(i.0, i.1)))
+        {
+            assert!(
+                strokes.edges.iter().all(|i| i.arc == 1.0),
+                "glyph {}",
+                glyph.index
+            );
+        }
+    }
+}
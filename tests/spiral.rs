@@ -0,0 +1,143 @@
+// fitting the spiral the characters sit on, and ordering by it only when it accounts for all of them
+
+use hts_prog6::{
+    decode_page, glyph, recognise, segment,
+    spiral::{MisfitKind, Placement},
+    synth::{generate, Layout},
+    Spiral, SpiralLayout, Strategy,
+};
+
+const SPIRAL: Spiral = Spiral {
+    centre: (400.0, 300.0),
+    start_radius: 280.0,
+    pitch: -20.0,
+};
+
+// where SPIRAL is along degrees from its start
+fn point(along: f64) -> (f64, f64) {
+    let (sin, cos) = along.to_radians().sin_cos();
+    let radius = SPIRAL.radius_at(along);
+
+    (
+        SPIRAL.centre.0 - radius * sin,
+        SPIRAL.centre.1 - radius * cos,
+    )
+}
+
+#[test]
+fn angles_go_anticlockwise_from_straight_up() {
+    let r = 50.0;
+    let (cx, cy) = SPIRAL.centre;
+
+    for (point, angle) in [
+        ((cx, cy - r), 0.0),
+        ((cx - r, cy), 90.0),
+        ((cx, cy + r), 180.0),
+        ((cx + r, cy), 270.0),
+    ] {
+        assert!(
+            (SPIRAL.angle_of(point) - angle).abs() < 1e-9,
+            "{:?} came out at {}",
+            point,
+            SPIRAL.angle_of(point)
+        );
+    }
+}
+
+#[test]
+fn a_fit_through_points_on_a_spiral_finds_it() {
+    // clear of 0 degrees, where a point could round either side of a turn
+    let along: Vec<f64> = (0..253).map(|i| f64::from(i * 10 + 5)).collect();
+    let points: Vec<(f64, f64)> = along.iter().map(|i| point(*i)).collect();
+
+    let fitted = Spiral::fit(&points, &along).unwrap();
+    assert!((fitted.centre.0 - SPIRAL.centre.0).abs() < 1e-6);
+    assert!((fitted.centre.1 - SPIRAL.centre.1).abs() < 1e-6);
+    assert!((fitted.start_radius - SPIRAL.start_radius).abs() < 1e-6);
+    assert!((fitted.pitch - SPIRAL.pitch).abs() < 1e-6);
+
+    // every point back on its own turn, at its own angle
+    for (point, along) in points.iter().zip(along.iter()) {
+        let placement = SPIRAL.place(*point, 0.0);
+
+        assert_eq!(placement.turn, (*along / 360.0).floor() as i32);
+        assert!((placement.along - along).abs() < 1e-6, "{:?}", placement);
+        assert!(placement.residual.abs() < 1e-6);
+    }
+
+    assert!(Spiral::fit(&points[..2], &along[..2]).is_none());
+}
+
+#[test]
+fn refining_a_rough_guess_settles_on_the_spiral() {
+    let along: Vec<f64> = (0..253).map(|i| f64::from(i * 10 + 5)).collect();
+    let points: Vec<(f64, f64)> = along.iter().map(|i| point(*i)).collect();
+    // every point guessed a few degrees out
+    let guess: Vec<f64> = (0..253).map(|i| f64::from(i * 10 + i % 7 + 2)).collect();
+
+    let (spiral, placements) = Spiral::refine(&points, &guess).unwrap();
+    assert!((spiral.pitch - SPIRAL.pitch).abs() < 0.1, "{:?}", spiral);
+
+    // the guess only has to be near enough for every point to land on its own turn
+    for (placement, along) in placements.iter().zip(along.iter()) {
+        assert_eq!(placement.turn, (*along / 360.0).floor() as i32);
+        assert!((placement.along - along).abs() < 1.0, "{:?}", placement);
+    }
+}
+
+#[test]
+fn characters_out_of_step_are_misfits() {
+    let at = |along: f64| SPIRAL.place(point(along), along);
+
+    let even: Vec<Placement> = (0..20).map(|i| at(f64::from(i * 10))).collect();
+    assert!(SpiralLayout::new(SPIRAL, &even).misfits.is_empty());
+
+    // the sixth doubled up on the fifth, and one missing after the twelfth
+    let mut uneven = even.clone();
+    uneven[5] = at(41.0);
+    uneven.remove(12);
+
+    let kinds: Vec<(i32, MisfitKind)> = SpiralLayout::new(SPIRAL, &uneven)
+        .misfits
+        .iter()
+        .map(|i| (i.glyph, i.kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            (5, MisfitKind::SharedSlot),
+            (6, MisfitKind::AfterGap),
+            (12, MisfitKind::AfterGap)
+        ]
+    );
+}
+
+#[test]
+fn ordering_never_reads_worse_than_the_walk() {
+    for seed in 0..3 {
+        let page = generate(seed, Layout::REALISTIC);
+        let right = |answer: String| {
+            answer
+                .chars()
+                .zip(page.text.chars())
+                .filter(|(a, b)| a == b)
+                .count()
+        };
+
+        let decoded = decode_page(&page.html()).unwrap();
+
+        // the same glyphs read in the order the walk found them
+        let mut walked = segment(&decoded.curves, &decoded.lines).unwrap();
+        glyph::attach_commands(&mut walked, &decoded.commands);
+        recognise(&mut walked, &decoded.curves, &Strategy::default()).unwrap();
+
+        let (ordered, walked) = (right(decoded.answer()), right(glyph::answer(&walked)));
+        assert!(
+            ordered >= walked,
+            "seed {}: {} read right after ordering, {} in the walk's order",
+            seed,
+            ordered,
+            walked
+        );
+    }
+}
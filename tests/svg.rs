@@ -0,0 +1,35 @@
+// the vector debug render of a decoded page
+
+use hts_prog6::{
+    decode_page,
+    svg::render,
+    synth::{generate, Layout},
+};
+
+#[test]
+fn every_glyph_gets_its_own_labelled_group() {
+    let decoded = decode_page(&generate(0, Layout::REALISTIC).html()).unwrap();
+    let svg = render(&decoded.commands, &decoded.glyphs);
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.ends_with("</svg>\n"));
+    assert_eq!(svg.matches("<g").count(), svg.matches("</g>").count());
+
+    assert_eq!(svg.matches("<g id=\"glyph-").count(), decoded.glyphs.len());
+    assert_eq!(svg.matches("class=\"bbox\"").count(), decoded.glyphs.len());
+    for glyph in decoded.glyphs.iter() {
+        assert_eq!(
+            svg.matches(&format!("<g id=\"glyph-{}\">", glyph.index))
+                .count(),
+            1,
+            "glyph {}",
+            glyph.index
+        );
+    }
+
+    // one arrow from every character to the next
+    assert_eq!(
+        svg.matches("class=\"order\"").count(),
+        decoded.glyphs.len() - 1
+    );
+}
@@ -0,0 +1,71 @@
+// the made up pages every other test decodes have to be ones the pipeline can read, whatever the seed
+
+use std::collections::HashSet;
+
+use hts_prog6::{
+    draw::commands,
+    order, rasterize, segment,
+    segment::analyze_character,
+    synth::{character, generate, Layout, ALPHABET},
+    Rect,
+};
+
+#[test]
+fn every_character_is_in_one_piece() {
+    for c in ALPHABET {
+        for turn in (0..360).step_by(10) {
+            for k in 0..8 {
+                let at = (400.0 + f64::from(k) * 0.13, 300.0 + f64::from(k) * 0.29);
+                let rects: Vec<Rect> = character(*c, turn, at)
+                    .iter()
+                    .flat_map(|i| i.rasterize().unwrap())
+                    .collect();
+                let all: HashSet<Rect> = rects.iter().copied().collect();
+
+                for start in all.iter() {
+                    assert_eq!(
+                        analyze_character(&rects, &[*start]).len(),
+                        all.len(),
+                        "{} turned {} at {:?}, growing from {:?}",
+                        *c as char,
+                        turn,
+                        at,
+                        start
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn every_seed_gives_a_page_that_segments() {
+    for seed in 0..12 {
+        let page = generate(seed, Layout::REALISTIC);
+        let (curves, lines) = rasterize(&commands(&page.draw_data).unwrap()).unwrap();
+
+        let mut glyphs =
+            segment(&curves, &lines).unwrap_or_else(|e| panic!("seed {}: {}", seed, e));
+        assert_eq!(glyphs.len(), page.text.len(), "seed {}", seed);
+
+        // every glyph is the whole of the character drawn there and nothing else
+        for (i, glyph) in glyphs.iter().enumerate() {
+            let drawn: HashSet<Rect> =
+                character(page.text.as_bytes()[i], 10 * i as i32, page.places[i])
+                    .iter()
+                    .flat_map(|i| i.rasterize().unwrap())
+                    .collect();
+            let found: HashSet<Rect> = glyph.rects.iter().copied().collect();
+
+            assert_eq!(found, drawn, "seed {} glyph {}", seed, i);
+        }
+
+        let layout = order(&mut glyphs).unwrap().unwrap();
+        assert!(
+            layout.misfits.is_empty(),
+            "seed {}: {:?}",
+            seed,
+            layout.misfits
+        );
+    }
+}
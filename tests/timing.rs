@@ -0,0 +1,84 @@
+// the deadline a run is timed against. a stage that ends past it fails the run, naming itself
+
+use std::{thread, time::Duration};
+
+use hts_prog6::{
+    decode_page_within, recognise_within,
+    synth::{generate, Layout},
+    timing::Stopwatch,
+    HTSError, Strategy,
+};
+
+mod common;
+
+use common::drawn;
+
+#[test]
+fn no_budget_stops_the_run_after_its_first_stage() {
+    let page = generate(0, Layout::REALISTIC);
+
+    for budget in [Duration::ZERO, Duration::from_nanos(1)] {
+        let mut clock = Stopwatch::new(Some(budget));
+
+        match decode_page_within(&page.html(), &Strategy::default(), &mut clock) {
+            Err(HTSError::Deadline(e)) => {
+                assert_eq!(e.stage, "parse");
+                assert_eq!(e.budget, budget);
+                assert!(e.elapsed >= budget);
+            }
+            other => panic!("a {:?} budget gave {:?}", budget, other.map(|i| i.answer())),
+        }
+
+        // nothing after the stage that overran was run
+        let stages: Vec<&str> = clock.timings().iter().map(|i| i.stage).collect();
+        assert_eq!(stages, ["parse"]);
+    }
+}
+
+#[test]
+fn the_stage_that_overran_is_the_one_named() {
+    let mut clock = Stopwatch::new(Some(Duration::from_millis(50)));
+
+    clock.stage("quick", || Ok::<_, HTSError>(())).unwrap();
+
+    let slow = clock.stage("slow", || {
+        thread::sleep(Duration::from_millis(60));
+        Ok::<_, HTSError>(())
+    });
+    match slow {
+        Err(HTSError::Deadline(e)) => assert_eq!(e.stage, "slow"),
+        other => panic!("overrunning gave {:?}", other),
+    }
+
+    assert!(clock.remaining() == Some(Duration::ZERO));
+}
+
+#[test]
+fn recognition_stops_part_way_once_the_clock_runs_out() {
+    let mut glyphs: Vec<_> = (0..4).map(|i| drawn(b'7', i * 10).0).collect();
+    let clock = Stopwatch::new(Some(Duration::ZERO));
+
+    match recognise_within(&mut glyphs, &[], &Strategy::Rules, &clock) {
+        Err(HTSError::Deadline(e)) => assert_eq!(e.stage, "recognise"),
+        other => panic!("recognising with no time left gave {:?}", other),
+    }
+    assert!(glyphs.iter().all(|i| i.recognition.is_none()));
+
+    recognise_within(&mut glyphs, &[], &Strategy::Rules, &Stopwatch::unlimited()).unwrap();
+    assert!(glyphs.iter().all(|i| i.recognition.is_some()));
+}
+
+#[test]
+fn no_deadline_never_runs_out() {
+    let page = generate(0, Layout::REALISTIC);
+    let mut clock = Stopwatch::unlimited();
+
+    let decoded = decode_page_within(&page.html(), &Strategy::default(), &mut clock).unwrap();
+    assert_eq!(decoded.glyphs.len(), page.text.len());
+    assert!(clock.remaining().is_none());
+    assert!(clock.check("after").is_ok());
+
+    let stages: Vec<&str> = decoded.timings.iter().map(|i| i.stage).collect();
+    assert_eq!(stages.first(), Some(&"parse"));
+    assert_eq!(stages.last(), Some(&"recognise"));
+}
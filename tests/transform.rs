@@ -0,0 +1,305 @@
+// turning geometry round by each of the 36 angles the mission turns its characters by, and back again
+
+use std::collections::HashSet;
+
+use hts_prog6::{
+    draw::{commands, DrawCommand},
+    rasterize, segment,
+    synth::{generate, Layout},
+    transform::{pixels, Affine, Resampling, Shape},
+    Frame, Rect,
+};
+
+const CENTRE: (f64, f64) = (350.0, 350.0);
+
+fn ring_angles() -> impl Iterator<Item = f64> {
+    (0..36).map(|i| f64::from(i * 10))
+}
+
+fn page_commands() -> Vec<DrawCommand> {
+    commands(&generate(6, Layout::REALISTIC).draw_data).unwrap()
+}
+
+fn close(a: (f64, f64), b: (f64, f64)) -> bool {
+    (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9
+}
+
+fn same_shape(a: &Shape, b: &Shape) -> bool {
+    match (a, b) {
+        (Shape::Line { from: f1, to: t1 }, Shape::Line { from: f2, to: t2 }) => {
+            close(*f1, *f2) && close(*t1, *t2)
+        }
+        (
+            Shape::Arc {
+                centre: c1,
+                radius: r1,
+                start: s1,
+                sweep: e1,
+            },
+            Shape::Arc {
+                centre: c2,
+                radius: r2,
+                start: s2,
+                sweep: e2,
+            },
+        ) => {
+            close(*c1, *c2)
+                && (r1 - r2).abs() < 1e-9
+                && ((s1 - s2).rem_euclid(360.0) < 1e-9
+                    || (s1 - s2).rem_euclid(360.0) > 360.0 - 1e-9)
+                && e1 == e2
+        }
+        _ => false,
+    }
+}
+
+// the share of a's pixels with one of b's on or next to them
+fn near(a: &HashSet<(i32, i32)>, b: &HashSet<(i32, i32)>) -> f64 {
+    let close = a
+        .iter()
+        .filter(|i| {
+            b.iter()
+                .any(|j| (i.0 - j.0).abs() <= 1 && (i.1 - j.1).abs() <= 1)
+        })
+        .count();
+    close as f64 / a.len() as f64
+}
+
+fn pixel_set(rects: &[Rect]) -> HashSet<(i32, i32)> {
+    pixels(rects).into_keys().collect()
+}
+
+#[test]
+fn inverse_undoes_every_ring_angle() {
+    for angle in ring_angles() {
+        let turn = Affine::rotation_about(CENTRE, angle);
+        let back = turn.then(&turn.inverse().unwrap());
+
+        for point in [(0.0, 0.0), (400.0, 123.5), (-17.25, 800.0)] {
+            assert!(
+                close(back.apply(point), point),
+                "{} degrees moved {:?}",
+                angle,
+                point
+            );
+        }
+    }
+}
+
+#[test]
+fn turning_the_other_way_is_the_inverse() {
+    for angle in ring_angles() {
+        let there = Affine::rotation_about(CENTRE, angle);
+        let back = Affine::rotation_about(CENTRE, -angle);
+        let inverse = there.inverse().unwrap();
+
+        for point in [(10.0, 20.0), (650.5, 49.0)] {
+            assert!(
+                close(back.apply(point), inverse.apply(point)),
+                "{} degrees",
+                angle
+            );
+        }
+    }
+}
+
+#[test]
+fn shapes_round_trip_at_every_ring_angle() {
+    let shapes: Vec<Shape> = page_commands().into_iter().map(Shape::from).collect();
+
+    for angle in ring_angles() {
+        let there = Affine::rotation_about(CENTRE, angle);
+        let back = Affine::rotation_about(CENTRE, -angle);
+
+        for shape in shapes.iter() {
+            let turned = there.apply_shape(shape).unwrap();
+            let returned = back.apply_shape(&turned).unwrap();
+
+            assert!(
+                same_shape(shape, &returned),
+                "{} degrees: {:?} came back as {:?}",
+                angle,
+                shape,
+                returned
+            );
+        }
+    }
+}
+
+#[test]
+fn commands_round_trip_at_every_ring_angle() {
+    let commands = page_commands();
+
+    for angle in ring_angles() {
+        let there = Affine::rotation_about(CENTRE, angle);
+        let back = Affine::rotation_about(CENTRE, -angle);
+
+        for command in commands.iter() {
+            let turned = there.apply_shape(&Shape::from(*command)).unwrap();
+            let returned = back.apply_shape(&turned).unwrap().to_command();
+
+            assert_eq!(*command, returned, "{} degrees", angle);
+        }
+    }
+}
+
+#[test]
+fn arcs_turn_their_start_angle() {
+    let arc = DrawCommand::Arc {
+        x: 300,
+        y: 200,
+        r: 4,
+        s: 330,
+        e: 180,
+    };
+
+    for angle in ring_angles() {
+        let turned = Affine::rotation_about((300.0, 200.0), angle)
+            .apply_command(&arc)
+            .unwrap();
+        let expected = DrawCommand::Arc {
+            x: 300,
+            y: 200,
+            r: 4,
+            s: (330 + angle as i32) % 360,
+            e: 180,
+        };
+
+        assert_eq!(turned, expected);
+    }
+}
+
+#[test]
+fn mirrored_arcs_are_refused() {
+    let mirror = Affine {
+        a: -1.0,
+        ..Affine::IDENTITY
+    };
+    let skew = Affine {
+        b: 0.5,
+        ..Affine::IDENTITY
+    };
+    let arc = Shape::Arc {
+        centre: (0.0, 0.0),
+        radius: 4.0,
+        start: 0.0,
+        sweep: 90.0,
+    };
+    let line = Shape::Line {
+        from: (0.0, 0.0),
+        to: (4.0, 2.0),
+    };
+
+    assert_eq!(mirror.apply_shape(&arc), None);
+    assert_eq!(skew.apply_shape(&arc), None);
+    assert!(mirror.apply_shape(&line).is_some());
+    assert!(Affine::scale(2.0).apply_shape(&arc).is_some());
+}
+
+#[test]
+fn quarter_turns_of_rects_are_exact() {
+    let (curves, lines) = rasterize(&page_commands()).unwrap();
+    let rects = segment(&curves, &lines).unwrap()[0].rects.clone();
+    let original = pixel_set(&rects);
+
+    for angle in [0.0, 90.0, 180.0, 270.0] {
+        let there = Affine::rotation_about(CENTRE, angle);
+        let back = Affine::rotation_about(CENTRE, -angle);
+
+        let turned = there.apply_rects(&rects, Resampling::Nearest);
+        assert_eq!(turned.len(), original.len(), "{} degrees", angle);
+
+        let returned = back.apply_rects(&turned, Resampling::Nearest);
+        assert_eq!(pixel_set(&returned), original, "{} degrees", angle);
+    }
+}
+
+#[test]
+fn rects_round_trip_at_every_ring_angle() {
+    let (curves, lines) = rasterize(&page_commands()).unwrap();
+    let glyphs = segment(&curves, &lines).unwrap();
+
+    for glyph in glyphs.iter().take(36) {
+        let original = pixel_set(&glyph.rects);
+
+        for angle in ring_angles() {
+            let there = Affine::rotation_about(glyph.centroid, angle);
+            let back = Affine::rotation_about(glyph.centroid, -angle);
+
+            let resamplings = [
+                Resampling::Nearest,
+                Resampling::Supersample {
+                    samples: 3,
+                    coverage: 0.25,
+                },
+            ];
+
+            for resampling in resamplings {
+                let turned = there.apply_rects(&glyph.rects, resampling);
+                let returned = pixel_set(&back.apply_rects(&turned, resampling));
+
+                // pixels can come back a pixel out, but the shape comes back whole and nothing new turns up
+                assert!(
+                    near(&original, &returned) > 0.9 && near(&returned, &original) > 0.9,
+                    "glyph {} at {} degrees with {:?}: {} of {} pixels came back where they were",
+                    glyph.index,
+                    angle,
+                    resampling,
+                    original.intersection(&returned).count(),
+                    original.len()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn turned_rects_land_near_the_turned_command() {
+    for command in page_commands().iter().take(60) {
+        let rects = command.rasterize().unwrap();
+
+        for angle in ring_angles() {
+            let turn = Affine::rotation_about(CENTRE, angle);
+
+            let drawn = pixel_set(&turn.apply_command(command).unwrap().rasterize().unwrap());
+            let moved = pixel_set(&turn.apply_rects(&rects, Resampling::Nearest));
+
+            // every moved pixel is within a pixel or so of one the turned command draws
+            for (x, y) in moved.iter() {
+                assert!(
+                    drawn
+                        .iter()
+                        .any(|i| (i.0 - x).abs() <= 2 && (i.1 - y).abs() <= 2),
+                    "{:?} at {} degrees: ({}, {}) is nowhere near",
+                    command,
+                    angle,
+                    x,
+                    y
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn frames_turn_both_ways() {
+    for angle in ring_angles() {
+        let frame = Frame::new((123.0, 456.0), angle);
+
+        for point in [(0.0, -6.0), (4.0, 2.0)] {
+            assert!(
+                close(frame.to_local(frame.to_canvas(point)), point),
+                "{} degrees",
+                angle
+            );
+        }
+
+        // the glyph's up points the way (0, -1) in its own coordinates lands
+        let top = frame.to_canvas((0.0, -1.0));
+        assert!(
+            close((top.0 - 123.0, top.1 - 456.0), frame.up()),
+            "{} degrees",
+            angle
+        );
+    }
+}
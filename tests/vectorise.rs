@@ -0,0 +1,154 @@
+// getting lines and arcs back out of nothing but the pixels they drew, and decoding a page from its divs
+
+use std::collections::HashSet;
+
+use hts_prog6::{
+    decode_page,
+    draw::{commands, parse_divs, ExtractionError},
+    rasterize, segment,
+    synth::{generate, Layout},
+    transform::pixels,
+    vectorise::{split_arcs, vectorise},
+    DrawCommand, HTSError, Rect,
+};
+
+fn pixel_set(rects: &[Rect]) -> HashSet<(i32, i32)> {
+    pixels(rects).into_keys().collect()
+}
+
+fn divs(rects: &[Rect]) -> String {
+    rects
+        .iter()
+        .map(|i| {
+            format!(
+                "<div style=\"position:absolute;left:{}px;top:{}px;width:{}px;height:{}px\"></div>\n",
+                i.0, i.1, i.2, i.3
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn single_commands_come_back_as_themselves() {
+    for command in commands(&generate(3, Layout::REALISTIC).draw_data)
+        .unwrap()
+        .iter()
+        .take(80)
+    {
+        let drawn = pixel_set(&command.rasterize().unwrap());
+        let found = vectorise(&command.rasterize().unwrap());
+
+        // a line is the only line through its pixels. an arc can come back starting a few degrees round from where
+        // it did, but it's still one arc over the same pixels
+        assert_eq!(found.len(), 1, "{:?} came back as {:?}", command, found);
+        assert_eq!(
+            found[0].is_line(),
+            command.is_line(),
+            "{:?} came back as {:?}",
+            command,
+            found
+        );
+
+        let redrawn = pixel_set(&found[0].rasterize().unwrap());
+        assert!(
+            redrawn.intersection(&drawn).count() * 10 >= drawn.len() * 9
+                && redrawn.difference(&drawn).count() <= 1,
+            "{:?} came back as {:?}",
+            command,
+            found
+        );
+    }
+}
+
+#[test]
+fn arcs_are_told_apart_from_lines() {
+    let (curves, lines) =
+        rasterize(&commands(&generate(3, Layout::REALISTIC).draw_data).unwrap()).unwrap();
+    let arcs: HashSet<(i32, i32)> = curves.iter().map(|i| (i.0, i.1)).collect();
+
+    for glyph in segment(&curves, &lines).unwrap().iter().take(36) {
+        let (found, _) = split_arcs(&glyph.rects, &vectorise(&glyph.rects));
+        let found: HashSet<(i32, i32)> = found.iter().map(|i| (i.0, i.1)).collect();
+        let truth: HashSet<(i32, i32)> = glyph
+            .rects
+            .iter()
+            .filter(|i| i.2 == 1 && i.3 == 1 && arcs.contains(&(i.0, i.1)))
+            .map(|i| (i.0, i.1))
+            .collect();
+
+        assert_eq!(found.is_empty(), truth.is_empty(), "glyph {}", glyph.index);
+        assert!(
+            found.intersection(&truth).count() * 10 >= truth.len() * 8,
+            "glyph {}: found {} of {} arc pixels",
+            glyph.index,
+            found.intersection(&truth).count(),
+            truth.len()
+        );
+    }
+}
+
+#[test]
+fn divs_read_back_as_rects() {
+    let rects = vec![(350, 103, 1, 1), (12, 40, 5, 1), (7, 8, 1, 13)];
+    assert_eq!(parse_divs(&divs(&rects)).unwrap(), rects);
+
+    // the way inspect prints them
+    assert_eq!(
+        parse_divs("left:12px;top:40px;width:5px;height:1px;\n").unwrap(),
+        vec![(12, 40, 5, 1)]
+    );
+
+    match parse_divs("<div style=\"left:1px;top:2px;width:3px\"></div>") {
+        Err(ExtractionError::BadDiv { offset, .. }) => assert_eq!(offset, 12),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn a_cut_short_array_says_where() {
+    // an arc, then a line that never finishes
+    match commands(&[402, 106, 3, 0, 270, 400, 100, 410, 100]) {
+        Ok(commands) => assert_eq!(commands.len(), 2),
+        other => panic!("{:?}", other),
+    }
+    match commands(&[402, 106, 3, 0, 270, 400, 100]) {
+        Err(ExtractionError::Truncated { offset, leftover }) => {
+            assert_eq!(offset, 5);
+            assert_eq!(leftover, [400, 100]);
+        }
+        other => panic!("{:?}", other),
+    }
+
+    let mut page = generate(3, Layout::REALISTIC);
+    page.draw_data.pop();
+    assert!(matches!(
+        decode_page(&page.html()),
+        Err(HTSError::Extraction(ExtractionError::Truncated { .. }))
+    ));
+}
+
+#[test]
+fn a_page_reads_the_same_from_its_divs() {
+    let page = generate(3, Layout::REALISTIC);
+    let (curves, lines) = rasterize(&commands(&page.draw_data).unwrap()).unwrap();
+
+    let from_commands = decode_page(&page.html()).unwrap();
+    let from_divs = decode_page(&divs(&[curves, lines].concat())).unwrap();
+
+    assert!(from_divs
+        .commands
+        .iter()
+        .any(|i| matches!(i, DrawCommand::Arc { .. })));
+
+    let (a, b) = (from_commands.answer(), from_divs.answer());
+    let same = a.chars().zip(b.chars()).filter(|(a, b)| a == b).count();
+    assert_eq!(a.len(), b.len());
+    assert!(
+        same * 100 >= a.len() * 98,
+        "{} of {} the same\n{}\n{}",
+        same,
+        a.len(),
+        a,
+        b
+    );
+}